@@ -5,16 +5,15 @@ use std::num::ParseFloatError;
 
 use anyhow::{Context, Result};
 use bytemuck::{Pod, Zeroable};
-use cgmath::{ElementWise, Matrix, Matrix3, Matrix4, MetricSpace, Point3, point3, Rad, SquareMatrix, vec3, Vector3};
+use cgmath::{ElementWise, Matrix, Matrix4, MetricSpace, Point3, point3, Rad, SquareMatrix, vec3, Vector3};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use winit::event::{DeviceEvent, ElementState};
 
-use webgpu_book::{Configurator, Content, PipelineConfiguration, RenderConfiguration, To, UniformInfo, VertexBufferInfo};
-use webgpu_book::transforms::invert;
+use webgpu_book::{Configurator, NoContent, PipelineConfiguration, RenderConfiguration, To, UniformInfo, VertexBufferInfo};
+use webgpu_book::transforms::{invert, Projection};
 
 use crate::common::{CmdArgs, Vertex, VertexN, VertexNC};
-use crate::common::light::{LightExamples, LightUniform, Model, OglCamera, UniformsData};
+use crate::common::light::{LightExamples, LightUniform, Model, OglCamera};
 use crate::common::surface_data::{Edges, Mesh, Quads};
 use crate::common::vertex_data::sphere_quads;
 
@@ -119,21 +118,22 @@ fn print_matrix(name: &str, value: Matrix4<f32>) {
     }
 }
 
-fn light<CU: UniformInfo>() -> Configurator<PipelineConfiguration> where OglCamera: To<CU> {
+// Orthographic view keeping the scene within `extent` of the origin unclipped however the camera turns
+fn light<CU: UniformInfo>(extent: f32) -> Configurator<PipelineConfiguration> where OglCamera: To<CU> {
     let z = 3.0;
     let camera = OglCamera::new(
         point3(0.0, 0.0, z),
         point3(0.0, 0.0, 0.0),
         Vector3::unit_y(),
         Rad((1.0 / z).asin() * 2.0) * 0.0,
-    );
+    ).with_projection(Projection::orthographic(extent.max(1.5)).with_near(z - extent).with_far(z + extent));
     LightExamples::configurator::<1, (), CU>(
         [Model::new(Matrix4::identity())],
         true,
         camera,
         LightUniform::example(),
         (),
-        Box::new(Controller::new(Rad(0.005)))
+        Box::new(NoContent)
     )
 }
 
@@ -192,6 +192,8 @@ fn main() -> Result<()> {
             )),
             |filename| load(filename, SCALE * 1.5),
         )?;
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
     let edges = CmdArgs::get_option::<usize>("--edges");
     let triangles = CmdArgs::get_option::<usize>("--edges");
     let save_image = CmdArgs::get_option::<String>("--save-image");
@@ -199,21 +201,23 @@ fn main() -> Result<()> {
     // let n = CmdArgs::next("1000").parse().expect("Invalid number");
     // let spheres = random_spheres(n, SCALE, rng);
     let total: f32 = spheres.iter().map(Sphere::volume).sum();
+    let extent = spheres.iter()
+        .map(|sphere| sphere.center.distance(point3(0.0, 0.0, 0.0)) + sphere.radius)
+        .fold(0.0, f32::max);
     println!("Sphere volume: avg = {:.5}, total = {total:.5}", total / spheres.len() as f32);
     // let spheres = two_spheres(SCALE, rng);
     // let spheres = unit_sphere(SCALE, rng);
 
-    let mut render = RenderConfiguration::new();
     if let Some(n) = triangles {
-        render.new_pass(vec![pipeline(include_str!("spheres_triangles.wgsl"))
+        render.new_pass(vec![pipeline(include_str!("spheres_triangles.wgsl"), extent)
             .with(quads(&spheres, n).cast::<VertexNC>().triangles().vertices())]);
     } else {
-        render.new_pass(vec![pipeline(include_str!("spheres.wgsl"))
+        render.new_pass(vec![pipeline(include_str!("spheres.wgsl"), extent)
             .with(Quads::join(spheres.iter().map(Sphere::billboards)).triangles().vertices())
             .with_cull_mode(None)]);
     }
     if let Some(n) = edges {
-        render.new_pass(vec![pipeline(include_str!("spheres_edges.wgsl"))
+        render.new_pass(vec![pipeline(include_str!("spheres_edges.wgsl"), extent)
             .with(quads(&spheres, n).cast::<Vertex>().edges().vertices())])
             .with_load(wgpu::LoadOp::Load);
     };
@@ -227,9 +231,9 @@ fn quads(spheres: &[Sphere], n: usize) -> Mesh<VertexNC, 4> {
     Quads::join(spheres.iter().map(|sphere| sphere.quads(n)))
 }
 
-fn pipeline(shader: &str) -> PipelineConfiguration {
+fn pipeline(shader: &str, extent: f32) -> PipelineConfiguration {
     PipelineConfiguration::new(shader)
-        .with(light::<CameraViewProjectUniform>())
+        .with(light::<CameraViewProjectUniform>(extent))
 }
 
 fn take_while<T, I: Iterator<Item=T>>(peekable: &mut Peekable<I>, p: fn(&T) -> bool) -> Vec<T> {
@@ -241,34 +245,7 @@ fn take_while<T, I: Iterator<Item=T>>(peekable: &mut Peekable<I>, p: fn(&T) -> b
 }
 
 
-struct Controller {
-    speed: Rad<f32>,
-    mouse_pressed: bool,
-}
-
-impl Controller {
-    fn new(speed: Rad<f32>) -> Self {
-        Self { speed, mouse_pressed: false }
-    }
-}
-
-impl Content<&mut UniformsData<1, ()>> for Controller {
-    fn input(&mut self, context: &mut UniformsData<1, ()>, event: &DeviceEvent) {
-        match *event {
-            DeviceEvent::Button { button: 1, state} =>
-                self.mouse_pressed = state == ElementState::Pressed,
-            DeviceEvent::MouseMotion { delta } => {
-                if self.mouse_pressed {
-                    context.camera.as_mut().transform(Matrix3::from_angle_y(-self.speed * delta.0 as f32) *
-                        Matrix3::from_angle_x(-self.speed * delta.1 as f32));
-                }
-            }
-            _ => (),
-        }
-    }
-}
-
-#[allow(clippy::indexing_slicing, clippy::match_on_vec_items)]
+#[allow(clippy::indexing_slicing)]
 fn load(cif_file: String, scale: f32) -> Result<Vec<Sphere>> {
     let mut atoms: Vec<Sphere> = vec![];
     let mut cell_length = point3(1.0, 1.0, 1.0);
@@ -0,0 +1,10 @@
+use webgpu_book::adapters;
+
+fn main() {
+    for adapter in adapters(wgpu::Backends::all()) {
+        let info = adapter.info;
+        println!("{} ({:?}, {:?})", info.name, info.backend, info.device_type);
+        println!("    max texture size: {}", adapter.limits.max_texture_dimension_2d);
+        println!("    max bind groups:  {}", adapter.limits.max_bind_groups);
+    }
+}
@@ -1,11 +1,10 @@
-use webgpu_book::{NoContent, WindowConfiguration};
+use webgpu_book::{FrameClock, NoContent, WindowConfiguration};
 use webgpu_book::window::show;
 
 fn main() -> ! {
     show(
-        &WindowConfiguration {
-            title: "rust::WebGPU",
-        },
+        &WindowConfiguration::new("rust::WebGPU").with_size(800, 600),
+        FrameClock::new(),
         |_window| Box::new(NoContent),
     );
 }
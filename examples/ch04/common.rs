@@ -1,10 +1,10 @@
 use webgpu_book::{PipelineConfiguration, VertexBufferInfo};
 
-pub use self::global_common::*;
-
 #[path = "../common/global_common.rs"]
 mod global_common;
 
+use self::global_common::RunExport;
+
 // Vertex with position and color
 
 #[repr(C)]
@@ -24,5 +24,5 @@ impl VertexBufferInfo for Vertex {
 pub fn run_example(title: &str, vertices: Vec<Vertex>, indices: Option<&[u16]>) -> ! {
     PipelineConfiguration::new(include_str!("triangle.wgsl"))
         .with_vertices_indices(vertices, indices, wgpu::PrimitiveTopology::TriangleList)
-        .run_title(title);
+        .run_export(title);
 }
@@ -1,5 +1,3 @@
-use core::u16;
-
 use crate::common::{run_example, Vertex};
 
 mod common;
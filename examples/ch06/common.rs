@@ -25,7 +25,7 @@ pub fn run_example<V: VertexBufferInfo>(
     PipelineConfiguration::new(shader_source)
         .with(MvpController::example(()))
         .with_vertices_indices(vertices, indices, topology)
-        .run_title(title);
+        .run_export(title);
 }
 
 
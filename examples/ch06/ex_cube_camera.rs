@@ -1,10 +1,10 @@
 use cgmath::{Angle, Deg, Rad};
-use winit::event::{DeviceEvent, ElementState};
+use winit::event::{ElementState, MouseButton};
 
-use webgpu_book::{Content, PipelineConfiguration, transforms::create_rotation};
+use webgpu_book::{FlyCamera, InputEvent, PipelineConfiguration, transforms::create_rotation};
 
-use crate::common::{Camera, CameraController, create_cube};
-use crate::common::mvp::MvpController;
+use crate::common::{Camera, CameraController, CmdArgs, create_cube, RunExport};
+use crate::common::mvp::{MvpController, MvpState};
 use crate::common::vertex_data::FACE_COLORS_CUBE;
 
 mod common;
@@ -17,16 +17,13 @@ struct CameraState {
 }
 
 impl CameraState {
-    fn input(&mut self, event: &DeviceEvent) {
+    fn input(&mut self, event: &InputEvent) {
         match *event {
-            DeviceEvent::Button {
-                button: 1, // Left Mouse Button
-                state,
-            } => self.mouse_pressed = state == ElementState::Pressed,
-            DeviceEvent::MouseMotion { delta } => {
-                if self.mouse_pressed {
-                    self.camera_controller.mouse_move(delta.0, delta.1);
-                }
+            InputEvent::MouseButton { button: MouseButton::Left, state, .. } => {
+                self.mouse_pressed = state == ElementState::Pressed;
+            }
+            InputEvent::MouseMotion { delta } if self.mouse_pressed => {
+                self.camera_controller.mouse_move(delta.0, delta.1);
             }
             _ => (),
         }
@@ -34,24 +31,33 @@ impl CameraState {
     }
 }
 
-impl Content<()> for MvpController<CameraState> {
-    fn input(&mut self, _context: (), event: &DeviceEvent) {
-        self.state.input(event);
-        self.set_view(self.state.camera.view());
+impl MvpState for CameraState {
+    fn input(controller: &mut MvpController<Self>, event: &InputEvent) {
+        controller.state.input(event);
+        controller.set_view(controller.state.camera.view());
     }
 }
 
 fn main() {
     let angle = Rad::full_turn() / 16.0;
     let model = create_rotation([angle, angle, angle]);
-    let camera = Camera::new((0.0, 0.0, -5.0), Deg(90.0), Deg(0.0));
-    let cube = FACE_COLORS_CUBE;
-    PipelineConfiguration::new(include_str!("cube_face_colors.wgsl"))
-        .with(MvpController::from_model_view(model, camera.view(), Deg(90.0).into(), CameraState {
+    // `--fly` swaps the camera for the library one: WASD to move, Q and E to go down and up, shift to hurry
+    let controller = if CmdArgs::has_option("--fly") {
+        let camera = FlyCamera::new((0.0, 0.0, -5.0), Deg(90.0), Deg(0.0))
+            .with_sensitivity(Rad(0.005))
+            .with_pitch_limit(Deg(60.0));
+        MvpController::fly(model, camera, Deg(90.0))
+    } else {
+        let camera = Camera::new((0.0, 0.0, -5.0), Deg(90.0), Deg(0.0));
+        MvpController::from_model_view(model, camera.view(), Deg(90.0).into(), CameraState {
             camera,
             camera_controller: CameraController::new(0.005),
             mouse_pressed: false,
-        }))
+        })
+    };
+    let cube = FACE_COLORS_CUBE;
+    PipelineConfiguration::new(include_str!("cube_face_colors.wgsl"))
+        .with(controller)
         .with(create_cube(cube.positions, cube.colors).triangles().vertices())
-        .run_title("Chapter 6 Controlled camera");
+        .run_export("Chapter 6 Controlled camera");
 }
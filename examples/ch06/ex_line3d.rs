@@ -1,6 +1,6 @@
 use webgpu_book::PipelineConfiguration;
 
-use crate::common::Vertex;
+use crate::common::{RunExport, Vertex};
 use crate::common::mvp::MvpController;
 
 mod common;
@@ -16,7 +16,7 @@ fn create_vertices() -> Vec<Vertex> {
 
 fn main() {
     PipelineConfiguration::new(include_str!("line3d.wgsl"))
-        .with(MvpController::example(()))
+        .with(MvpController::orbit_example())
         .with_vertices(create_vertices(), wgpu::PrimitiveTopology::LineStrip)
-        .run_title("Chapter 6 Line");
+        .run_export("Chapter 6 Line");
 }
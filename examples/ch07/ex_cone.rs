@@ -1,7 +1,7 @@
 use common::surface_data::Edges;
 
 use crate::common::mvp::AnimationState;
-use crate::common::Vertex;
+use crate::common::{RunExport, Vertex};
 use crate::common::vertex_data::Cone;
 
 mod common;
@@ -9,5 +9,5 @@ mod common;
 fn main() {
     Edges::from(Cone::triangles(0.5, 1.5, 2.0, 12).cast::<Vertex>()).into_config()
         .with(AnimationState::example())
-        .run_title("Chapter 7. Cone");
+        .run_export("Chapter 7. Cone");
 }
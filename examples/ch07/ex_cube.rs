@@ -1,6 +1,6 @@
 use common::surface_data::Edges;
 use crate::common::mvp::AnimationState;
-use crate::common::Vertex;
+use crate::common::{RunExport, Vertex};
 
 mod common;
 
@@ -32,5 +32,5 @@ fn create_mesh() -> Edges<Vertex> {
 fn main() {
     create_mesh().into_config()
         .with(AnimationState::example())
-        .run_title("Chapter 7. Cube");
+        .run_export("Chapter 7. Cube");
 }
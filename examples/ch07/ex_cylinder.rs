@@ -1,4 +1,4 @@
-use crate::common::{Vertex};
+use crate::common::{RunExport, Vertex};
 use crate::common::mvp::AnimationState;
 use crate::common::vertex_data::Cylinder;
 
@@ -9,5 +9,5 @@ fn main() {
         .cast::<Vertex>()
         .edges().into_config()
         .with(AnimationState::example())
-        .run_title("Chapter 7. Cylinder");
+        .run_export("Chapter 7. Cylinder");
 }
@@ -1,7 +1,7 @@
 use cgmath::point3;
 
 use crate::common::mvp::AnimationState;
-use crate::common::Vertex;
+use crate::common::{RunExport, Vertex};
 use crate::common::vertex_data::sphere_quads;
 
 mod common;
@@ -11,5 +11,5 @@ fn main() {
     sphere_quads(point3(0.0, 0.0, 0.0), 1.7, 20, 20, vertex_f).edges()
         .into_config()
         .with(AnimationState::example())
-        .run_title("Chapter 7. Sphere");
+        .run_export("Chapter 7. Sphere");
 }
@@ -1,7 +1,7 @@
 use webgpu_book::PipelineConfiguration;
 
 use crate::common::mvp::AnimationState;
-use crate::common::Vertex;
+use crate::common::{RunExport, Vertex};
 use crate::common::vertex_data::Torus;
 
 #[allow(clippy::duplicate_mod)]
@@ -14,5 +14,5 @@ pub fn pipeline() -> PipelineConfiguration {
 
 #[allow(dead_code)]
 fn main() {
-    pipeline().run_title("Chapter 7. Torus");
+    pipeline().run_export("Chapter 7. Torus");
 }
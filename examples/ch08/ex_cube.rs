@@ -1,6 +1,6 @@
 use core::iter::zip;
 
-use crate::common::{ColorLight, VertexN};
+use crate::common::{ColorLight, RunExport, VertexN};
 use crate::common::surface_data::Quads;
 use crate::common::vertex_data::{FACE_COLORS_CUBE, i8_as_f32};
 
@@ -16,5 +16,5 @@ fn create_quads() -> Quads<VertexN> {
 
 fn main() {
     ColorLight::example(create_quads().triangles())
-        .run_title("Chapter 8. Cube");
+        .run_export("Chapter 8. Cube");
 }
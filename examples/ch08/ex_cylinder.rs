@@ -1,4 +1,4 @@
-use crate::common::{ColorLight, VertexN};
+use crate::common::{ColorLight, RunExport, VertexN};
 use crate::common::vertex_data::Cylinder;
 
 mod common;
@@ -7,5 +7,5 @@ fn main() {
     let triangles = Cylinder::quads(0.5, 1.5, 1.5, 30, 0.0, 0.0).cast::<VertexN>().triangles();
     ColorLight::example(triangles)
         .with_cull_mode(Some(wgpu::Face::Back))
-        .run_title("Chapter 8. Cylinder");
+        .run_export("Chapter 8. Cylinder");
 }
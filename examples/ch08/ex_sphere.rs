@@ -1,11 +1,11 @@
 use cgmath::point3;
 
-use crate::common::{ColorLight, VertexN};
+use crate::common::{ColorLight, RunExport, VertexN};
 use crate::common::vertex_data::sphere_triangles;
 
 mod common;
 
 fn main() {
     ColorLight::example(sphere_triangles(point3(0.0, 0.0, 0.0), 1.5, 10, 20, &|position, normal, _lat_lon| VertexN::new(position, normal)))
-        .run_title("Chapter 8. Sphere");
+        .run_export("Chapter 8. Sphere");
 }
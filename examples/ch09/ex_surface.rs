@@ -1,4 +1,4 @@
-use crate::common::{CmdArgs, VertexNC};
+use crate::common::{CmdArgs, RunExport, VertexNC};
 use crate::common::colormap::Colormap;
 use crate::common::light::TwoSideLight;
 use crate::common::surface_data::Surface;
@@ -11,5 +11,5 @@ fn main() {
     let colormap = &Colormap::by_name(CmdArgs::next("jet").as_str());
     let (name, triangles) = Surface::read_args_triangles(colormap, true);
     TwoSideLight::example(include_str!("shader.wgsl"), triangles.cast::<VertexNC>())
-        .run_title(format!("Chapter 09. Surface ({name})").as_str());
+        .run_export(format!("Chapter 09. Surface ({name})").as_str());
 }
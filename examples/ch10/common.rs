@@ -13,6 +13,6 @@ pub fn run_example(title: &str, triangles: Triangles<VertexNT>) -> ! {
 
     TwoSideLight::example(include_str!("shader.wgsl"), triangles)
         .with_textures([TextureInfo::repeated(format!("examples/ch10/assets/{texture_file}.png"))])
-        .run_title(title)
+        .run_export(title)
 }
 
@@ -1,6 +1,6 @@
 use webgpu_book::TextureInfo;
 
-use crate::common::CmdArgs;
+use crate::common::{CmdArgs, RunExport};
 use crate::common::colormap::Colormap;
 use crate::common::light::TwoSideLight;
 use crate::common::surface_data::Surface;
@@ -15,5 +15,5 @@ fn main() {
 
     TwoSideLight::example(include_str!("shader.wgsl"), triangles)
         .with_textures([TextureInfo::repeated(format!("examples/ch11/assets/{texture_file}.png"))])
-        .run_title(format!("Chapter 11. Surface {name}").as_str());
+        .run_export(format!("Chapter 11. Surface {name}").as_str());
 }
@@ -3,7 +3,8 @@ use cgmath::{Matrix4, SquareMatrix, vec3};
 use webgpu_book::{PipelineConfiguration, TextureInfo};
 
 use crate::common::colormap::Colormap;
-use crate::common::light::{LightExamples, TwoSideLight};
+use crate::common::light::{LightExamples, Model, TwoSideLight};
+use crate::common::material::Material;
 use crate::common::surface_data::{Edges, Surface, Triangles};
 
 pub use self::global_common::*;
@@ -11,7 +12,7 @@ pub use self::global_common::*;
 #[path = "../common/global_common.rs"]
 mod global_common;
 
-pub fn example_models<const T: usize>(triangles: Triangles<VertexNCT>, models: [Matrix4<f32>; T], instances: bool) -> PipelineConfiguration {
+pub fn example_models<const T: usize>(triangles: Triangles<VertexNCT>, models: [Model; T], instances: bool) -> PipelineConfiguration {
     let texture_file = CmdArgs::next("whitesquare2");
     let is_two_side = CmdArgs::next_bool("Is two side", false);
 
@@ -22,7 +23,8 @@ pub fn example_models<const T: usize>(triangles: Triangles<VertexNCT>, models: [
         .with(LightExamples::models(light_aux, models, instances))
         .with_cull_mode(None)
         .with(LightExamples::read_args_wireframe(triangles))
-        .with_textures([TextureInfo::repeated(format!("examples/ch11/assets/{texture_file}.png"))])
+        .with_textures([texture_file.as_str(), "redsquare2", "greensquare2", "bluesquare2"]
+            .map(|file| TextureInfo::repeated(format!("examples/ch11/assets/{file}.png"))))
 }
 
 #[allow(dead_code, clippy::indexing_slicing)]
@@ -36,12 +38,20 @@ pub fn multi_pipeline(surface: &Surface, instances: bool) -> PipelineConfigurati
     let scale = 1.0 / (COLS - 1) as f32;
     let scale_m = Matrix4::from_scale(scale);
 
-    let mut models = [Matrix4::identity(); ROWS * COLS];
+    // Every tile gets its own tint, texture and sides, shininess grows along the rows
+    let hues = Colormap::by_name("hsv");
+    let tints = hues.interpolator((0.0, (ROWS * COLS) as f32));
+    let mut models = [(); ROWS * COLS].map(|()| Model::new(Matrix4::identity()));
     for r in 0..ROWS {
         for c in 0..COLS {
             let x = r as f32 - (COLS - 1) as f32 / 2.0;
             let y = c as f32 - (ROWS - 1) as f32 / 2.0;
-            models[r * COLS + c] = Matrix4::from_translation(vec3(x, y, 0.0)) * scale_m;
+            let index = r * COLS + c;
+            let material = Material::new(tints.interpolate(index as f32), 0.0, 0.5)
+                .with_shininess(4.0 * 2.0_f32.powi(c as i32))
+                .with_texture((r % 4) as u32)
+                .with_two_side(r % 2 == 1);
+            models[index] = Model::new(Matrix4::from_translation(vec3(x, y, 0.0)) * scale_m).with_material(material);
         }
     }
 
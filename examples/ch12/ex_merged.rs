@@ -2,6 +2,7 @@ use cgmath::{Matrix4, SquareMatrix, vec3};
 
 use crate::common::{example_models, VertexNCT};
 use crate::common::colormap::Colormap;
+use crate::common::light::Model;
 use crate::common::surface_data::{Mesh, Surface};
 
 mod common;
@@ -25,6 +26,6 @@ fn main() {
             })
         ));
 
-    example_models(triangles, [Matrix4::identity()], true)
+    example_models(triangles, [Model::new(Matrix4::identity())], true)
         .run_title("Chapter 12. Merged vertices");
 }
@@ -1,10 +1,11 @@
 use cgmath::point3;
 
-use webgpu_book::RenderConfiguration;
+use webgpu_book::{PipelineConfiguration, RenderConfiguration};
 
-use crate::common::{edges_pipeline, VertexNC};
+use crate::common::{CmdArgs, VertexC, VertexNC};
 use crate::common::colormap::Colormap;
-use crate::common::light::TwoSideLight;
+use crate::common::light::{LightExamples, LightUniform, TwoSideLight};
+use crate::common::material::Material;
 use crate::common::surface_data::Surface;
 
 mod common;
@@ -16,17 +17,26 @@ fn main() -> ! {
     let surface = Surface::by_name("sphere");
     let colormap = &Colormap::by_name("jet");
 
-    let edges = edges_pipeline(surface.edges(point3(1.0, 1.0, 1.0)).cast());
-
-    let faces = TwoSideLight::example(
-        include_str!("../ch09/shader.wgsl"),
-        surface.triangles(colormap, false).cast::<VertexNC>(),
-    );
-
-    let axes = edges_pipeline(surface.axes(2.5));
-
+    // One controller updates the camera and light buffers the three pipelines share
     let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    let light_aux = TwoSideLight::new(CmdArgs::next_bool("Is two side", false));
+    LightExamples::shared(&mut render, LightUniform::example(), Material::example(), light_aux);
+
+    let faces = PipelineConfiguration::new(include_str!("../ch09/shader.wgsl"))
+        .with_shared_uniforms()
+        .with_cull_mode(None)
+        .with(LightExamples::read_args_wireframe(surface.triangles(colormap, false).cast::<VertexNC>()));
+    let edges = PipelineConfiguration::new(include_str!("mesh.wgsl"))
+        .with_shared_uniforms()
+        .with(surface.edges(point3(1.0, 1.0, 1.0)).cast::<VertexC>().vertices());
+    let axes = PipelineConfiguration::new(include_str!("mesh.wgsl"))
+        .with_shared_uniforms()
+        .with(surface.axes(2.5).vertices());
+
     render.new_pass(vec![faces]);
-    render.new_pass(vec![edges, axes]).with_load(wgpu::LoadOp::Load);
+    render.new_pass(vec![edges, axes])
+        .with_load(wgpu::LoadOp::Load)
+        .with_depth_ops(wgpu::LoadOp::Load, true);
     render.run_title(format!("Chapter 12. Two-pass rendering ({})", surface.name()).as_str())
 }
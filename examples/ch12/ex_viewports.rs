@@ -0,0 +1,33 @@
+use webgpu_book::{PipelineConfiguration, RenderConfiguration, Viewport};
+
+use crate::common::{CmdArgs, edges_pipeline, VertexNC};
+use crate::common::colormap::Colormap;
+use crate::common::light::TwoSideLight;
+use crate::common::surface_data::Surface;
+
+mod common;
+
+fn surface_pipeline(name: &str, viewport: Viewport) -> PipelineConfiguration {
+    let colormap = &Colormap::by_name("jet");
+    TwoSideLight::example(
+        include_str!("../ch09/shader.wgsl"),
+        Surface::by_name(name).triangles(colormap, false).cast::<VertexNC>(),
+    ).with_viewport(viewport)
+}
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+
+    let left = CmdArgs::next("klein");
+    let right = CmdArgs::next("seashell");
+
+    render.new_pass(vec![
+        surface_pipeline(left.as_str(), Viewport::new(0.0, 0.0, 0.5, 1.0)),
+        surface_pipeline(right.as_str(), Viewport::new(0.5, 0.0, 0.5, 1.0)),
+    ]);
+    render.new_pass(vec![edges_pipeline(Surface::by_name("sphere").axes(2.5))])
+        .with_load(wgpu::LoadOp::Load)
+        .with_viewport(Viewport::new(0.0, 0.75, 0.25, 0.25).with_scissor(true));
+    render.run_title(format!("Chapter 12. Viewports ({left} | {right})").as_str())
+}
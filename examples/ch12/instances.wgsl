@@ -1,4 +1,5 @@
 @group(0) @binding(0) var<uniform> model_stride_u: array<ModelUniforms, 35>;
+@group(0) @binding(1) var<uniform> material_stride_u: array<Material, 35>;
 
 struct Output {
     @builtin(position) position: vec4<f32>,
@@ -6,6 +7,7 @@ struct Output {
     @location(1) v_normal: vec4<f32>,
     @location(2) v_uv: vec2<f32>,
     @location(3) v_color: vec4<f32>,
+    @location(4) @interpolate(flat) v_instance: u32,
 }
 
 @vertex
@@ -19,13 +21,33 @@ fn vs_main(in: VertexNCT) -> Output {
     output.v_normal = model_u.normals * in.normal;
     output.v_uv = in.uv;
     output.v_color = in.color;
+    output.v_instance = in.instance_index;
     return output;
 }
 
 @group(1) @binding(0) var texture_data: texture_2d<f32>;
 @group(1) @binding(1) var texture_sampler: sampler;
+@group(1) @binding(2) var red_texture: texture_2d<f32>;
+@group(1) @binding(3) var red_sampler: sampler;
+@group(1) @binding(4) var green_texture: texture_2d<f32>;
+@group(1) @binding(5) var green_sampler: sampler;
+@group(1) @binding(6) var blue_texture: texture_2d<f32>;
+@group(1) @binding(7) var blue_sampler: sampler;
+
+// All textures are sampled, textureSample has to stay in uniform control flow
+fn model_texture(index: u32, uv: vec2<f32>) -> vec3<f32> {
+    var samples = array<vec3<f32>, 4>(
+        textureSample(texture_data, texture_sampler, uv).rgb,
+        textureSample(red_texture, red_sampler, uv).rgb,
+        textureSample(green_texture, green_sampler, uv).rgb,
+        textureSample(blue_texture, blue_sampler, uv).rgb,
+    );
+    return samples[min(index, 3u)];
+}
 
 @fragment
 fn fs_main(in: Output) -> @location(0) vec4<f32> {
-    return color(in.v_position, in.v_normal, textureSample(texture_data, texture_sampler, in.v_uv).rgb + in.v_color.rgb);
+    let material = material_stride_u[in.v_instance];
+    let color = model_texture(material.texture, in.v_uv) + in.v_color.rgb;
+    return color_model(in.v_position, in.v_normal, color, material);
 }
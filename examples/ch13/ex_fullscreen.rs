@@ -0,0 +1,21 @@
+use webgpu_book::{PipelineConfiguration, RenderConfiguration};
+
+use crate::global_common::CmdArgs;
+
+#[path = "../common/global_common.rs"]
+mod global_common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    CmdArgs::read_post(&mut render);
+
+    let shader = CmdArgs::next_known("Shader", &["raymarch", "spheres"]);
+    let source = match shader.as_str() {
+        "spheres" => include_str!("spheres.wgsl"),
+        _ => include_str!("raymarch.wgsl"),
+    };
+
+    render.new_pass(vec![PipelineConfiguration::fullscreen(source)]);
+    render.run_title(format!("Chapter 13. Fullscreen shader: {shader}").as_str())
+}
@@ -0,0 +1,24 @@
+use webgpu_book::{AttachmentConfiguration, PipelineConfiguration, RenderConfiguration};
+
+use crate::global_common::CmdArgs;
+
+#[path = "../common/global_common.rs"]
+mod global_common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+
+    let scale: f32 = CmdArgs::next("0.25").parse().expect("Pixel scale");
+    render.add_attachment(AttachmentConfiguration::new("scene")
+        .with_scale(scale)
+        .with_filter(wgpu::FilterMode::Nearest));
+
+    // Declared before its producer, the render graph runs it last.
+    render.new_pass(vec![PipelineConfiguration::fullscreen(include_str!("retro.wgsl")).with_input("scene")])
+        .with_label("Retro");
+    render.new_pass(vec![PipelineConfiguration::fullscreen(include_str!("spheres.wgsl"))])
+        .with_target("scene")
+        .with_label("Scene");
+    render.run_title(format!("Chapter 13. Render graph: scale {scale}").as_str())
+}
@@ -0,0 +1,66 @@
+use core::time::Duration;
+
+use cgmath::point3;
+
+use webgpu_book::{PipelineConfiguration, RenderConfiguration, ToneMapping};
+
+use crate::common::{CmdArgs, VertexC, VertexNC};
+use crate::common::colormap::Colormap;
+use crate::common::light::{LightExamples, LightUniform, TwoSideLight};
+use crate::common::material::Material;
+use crate::common::surface_data::Surface;
+
+#[path = "../common/global_common.rs"]
+mod common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    CmdArgs::read_post(&mut render);
+    render.with_screenshot_key(winit::event::VirtualKeyCode::F12, "hdr_{}.png");
+    if CmdArgs::has_option("--profile") {
+        render.profile(Some(Duration::from_secs(2)));
+    }
+    let ldr = CmdArgs::has_option("--ldr");
+    // Bright enough for the specular highlights to pass 1.0
+    let light = LightUniform::read_args()
+        .unwrap_or_else(|| LightUniform::new(point3(10.0, 5.0, -3.0), point3(1.0, 1.0, 1.0), 0.1, 1.4, 3.0, 30.0));
+
+    let surface = Surface::read_args_surface();
+    let tone_mapping = match CmdArgs::next_known("Tone mapping", &["aces", "reinhard", "clamp"]).as_str() {
+        "reinhard" => ToneMapping::Reinhard,
+        "clamp" => ToneMapping::Clamp,
+        _ => ToneMapping::Aces,
+    };
+    let colormap = &Colormap::by_name("jet");
+
+    LightExamples::shared(&mut render, light, Material::example(), TwoSideLight::new(true));
+
+    let sky = PipelineConfiguration::fullscreen(include_str!("sky.wgsl"));
+    let faces = PipelineConfiguration::new(include_str!("../ch09/shader.wgsl"))
+        .with_shared_uniforms()
+        .with_cull_mode(None)
+        .with(LightExamples::read_args_wireframe(surface.triangles(colormap, false).cast::<VertexNC>()));
+    let edges = PipelineConfiguration::new(include_str!("../ch12/mesh.wgsl"))
+        .with_shared_uniforms()
+        .with(surface.edges(point3(1.0, 1.0, 1.0)).cast::<VertexC>().vertices());
+    let axes = PipelineConfiguration::new(include_str!("../ch12/mesh.wgsl"))
+        .with_shared_uniforms()
+        .with(surface.axes(2.5).vertices());
+
+    // Only the faces render in HDR, their resolve blending over the sky before the lines are drawn
+    render.new_pass(vec![sky.with_label("Sky")])
+        .with_label("Sky")
+        .with_depth(None);
+    let faces = render.new_pass(vec![faces.with_label("Faces")])
+        .with_label("Faces")
+        .with_load(wgpu::LoadOp::Load);
+    if !ldr {
+        faces.with_format(wgpu::TextureFormat::Rgba16Float).with_tone_mapping(tone_mapping);
+    }
+    render.new_pass(vec![edges.with_label("Edges"), axes.with_label("Axes")])
+        .with_label("Lines")
+        .with_load(wgpu::LoadOp::Load)
+        .with_depth_ops(wgpu::LoadOp::Load, true);
+    render.run_title(format!("Chapter 13. HDR faces over an LDR sky ({})", surface.name()).as_str())
+}
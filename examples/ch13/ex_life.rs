@@ -0,0 +1,36 @@
+use webgpu_book::{ComputeConfiguration, FeedbackConfiguration, PipelineConfiguration, RenderConfiguration, TextureInfo};
+
+use crate::global_common::CmdArgs;
+
+#[path = "../common/global_common.rs"]
+mod global_common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    let compute = CmdArgs::has_option("--compute");
+
+    let seed = CmdArgs::next_known("Seed", &["random", "greatcanyon", "yellowstone"]);
+    let feedback = match seed.as_str() {
+        "random" => FeedbackConfiguration::new("life", 320, 200),
+        image => FeedbackConfiguration::seeded("life", &TextureInfo::repeated(format!("examples/ch10/assets/{image}.png")))
+            .expect("Seed image loaded"),
+    };
+    render.add_feedback(feedback.with_filter(wgpu::FilterMode::Nearest));
+
+    // The same rules step the simulation in a fragment or a compute shader
+    let rules = format!("const RANDOM_SEED: bool = {};\n{}", seed == "random", include_str!("life_rules.wgsl"));
+    let simulation = if compute {
+        let step = format!("{rules}\n{}", include_str!("life_compute.wgsl"));
+        render.new_compute_pass(ComputeConfiguration::new(&step).with_frame_uniform().with_input("life").with_label("Step"))
+    } else {
+        let step = format!("{rules}\n{}", include_str!("life_step.wgsl"));
+        render.new_pass(vec![PipelineConfiguration::fullscreen(&step).with_input("life").with_label("Step")])
+    };
+    simulation
+        .with_target("life")
+        .with_label("Simulation");
+    render.new_pass(vec![PipelineConfiguration::fullscreen(include_str!("life_show.wgsl")).with_input("life")])
+        .with_label("Display");
+    render.run_title(format!("Chapter 13. Game of Life: {seed}").as_str())
+}
@@ -0,0 +1,55 @@
+use webgpu_book::{PipelineConfiguration, RenderConfiguration, TextureInfo};
+
+use crate::common::CmdArgs;
+use crate::common::colormap::Colormap;
+use crate::common::light::{LightExamples, LightUniform};
+use crate::common::material::Material;
+use crate::common::surface_data::Surface;
+
+#[path = "../common/global_common.rs"]
+mod common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    CmdArgs::read_post(&mut render);
+    let light = LightUniform::read_args().unwrap_or_else(LightUniform::three_point);
+    let material = Material::read_args();
+    let camera = LightExamples::read_camera();
+    let timeline = LightExamples::read_timeline(&camera);
+    let projection = camera.projection_settings();
+    let maps = [
+        ("base_color_map", CmdArgs::get_option::<String>("--base-map")),
+        ("metallic_roughness_map", CmdArgs::get_option::<String>("--metallic-roughness-map")),
+    ];
+
+    let colormap = &Colormap::by_name(CmdArgs::next("cooper").as_str());
+    let (name, triangles) = Surface::read_args_triangles(colormap, true);
+    LightExamples::shared_camera(&mut render, camera, timeline, light, material, ());
+
+    // Every map given replaces the default factor of one with a texture sample
+    let mut textures = vec![];
+    let mut declarations = String::new();
+    let mut samples = String::new();
+    for (map, file) in maps {
+        let Some(file) = file else { continue };
+        let binding = textures.len() * 2;
+        declarations.push_str(&format!(
+            "@group(1) @binding({binding}) var {map}_texture: texture_2d<f32>;\n\
+             @group(1) @binding({}) var {map}_sampler: sampler;\n",
+            binding + 1,
+        ));
+        samples.push_str(&format!("    {map} = textureSample({map}_texture, {map}_sampler, uv);\n"));
+        textures.push(TextureInfo::repeated(file));
+    }
+    let shader = format!("{declarations}\nfn sample_maps(uv: vec2<f32>) {{\n{samples}}}\n{}", include_str!("pbr.wgsl"));
+
+    let surface = PipelineConfiguration::new(&shader)
+        .with_shared_uniforms()
+        .with_cull_mode(None)
+        .with(triangles.vertices())
+        .with_textures(textures)
+        .with_label("Surface");
+    render.new_pass(vec![surface]).with_label("Surface").with_depth_projection(projection);
+    render.run_title(format!("Chapter 13. Physically based materials ({name})").as_str())
+}
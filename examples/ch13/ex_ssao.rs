@@ -0,0 +1,79 @@
+use cgmath::point3;
+
+use webgpu_book::{AmbientOcclusion, PipelineConfiguration, RenderConfiguration};
+
+use crate::common::{CmdArgs, VertexNC};
+use crate::common::colormap::Colormap;
+use crate::common::light::{LightExamples, LightUniform, TwoSideLight};
+use crate::common::material::Material;
+use crate::common::surface_data::Surface;
+
+#[path = "../common/global_common.rs"]
+mod common;
+
+fn main() -> ! {
+    let mut render = RenderConfiguration::new();
+    CmdArgs::read_export(&mut render);
+    CmdArgs::read_post(&mut render);
+    let enabled = !CmdArgs::has_option("--no-ssao");
+    let radius = CmdArgs::get_option("--radius").unwrap_or(0.5);
+    let samples = CmdArgs::get_option("--samples").unwrap_or(16);
+    let blur = CmdArgs::get_option("--blur").unwrap_or(2);
+    let camera = LightExamples::read_camera();
+    let timeline = LightExamples::read_timeline(&camera);
+    let projection = camera.projection_settings();
+    // A strong ambient term by default, the one occlusion darkens
+    let light = LightUniform::read_args()
+        .unwrap_or_else(|| LightUniform::new(point3(10.0, 5.0, -3.0), point3(1.0, 1.0, 1.0), 0.6, 0.4, 0.4, 30.0));
+
+    let surface = Surface::read_args_surface();
+    let colormap = &Colormap::by_name(CmdArgs::next("jet").as_str());
+
+    LightExamples::shared_camera(&mut render, camera, timeline, light, Material::example(), TwoSideLight::new(true));
+
+    let occlusion = if enabled {
+        render.with_ambient_occlusion(AmbientOcclusion::new()
+            .with_radius(radius)
+            .with_samples(samples)
+            .with_blur(blur));
+        let geometry = PipelineConfiguration::new(&format!(
+            "{}\n{}\n{}",
+            AmbientOcclusion::FUNCTIONS,
+            include_str!("surface.wgsl"),
+            include_str!("surface_geometry.wgsl"),
+        ));
+        render.new_pass(vec![geometry
+            .with_shared_uniforms()
+            .with_cull_mode(None)
+            .with(surface.triangles(colormap, false).cast::<VertexNC>().vertices())
+            .with_label("Geometry")])
+            .with_label("Geometry")
+            .with_target(AmbientOcclusion::GEOMETRY)
+            .with_load(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT))
+            .with_depth_projection(projection);
+        format!("textureLoad({}_texture, vec2<i32>(position.xy), 0).r", AmbientOcclusion::OCCLUSION)
+    } else {
+        "1.0".to_owned()
+    };
+
+    let mut lit = PipelineConfiguration::new(&format!(
+        "fn occlusion(position: vec4<f32>) -> f32 {{ return {occlusion}; }}\n{}\n{}",
+        include_str!("surface.wgsl"),
+        include_str!("surface_lit.wgsl"),
+    ))
+        .with_shared_uniforms()
+        .with_cull_mode(None)
+        .with(surface.triangles(colormap, false).cast::<VertexNC>().vertices())
+        .with_label("Surface");
+    if enabled {
+        lit = lit.with_input(AmbientOcclusion::OCCLUSION);
+    }
+    let lit = render.new_pass(vec![lit]).with_label("Surface").with_depth_projection(projection);
+    if enabled {
+        // The geometry pass laid down the depth, only the visible fragments are shaded
+        lit.with_depth_ops(wgpu::LoadOp::Load, true);
+    }
+
+    let mode = if enabled { "SSAO" } else { "no SSAO" };
+    render.run_title(format!("Chapter 13. Ambient occlusion ({}, {mode})", surface.name()).as_str())
+}
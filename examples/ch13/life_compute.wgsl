@@ -0,0 +1,8 @@
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(target_u.size);
+    let cell = vec2<i32>(id.xy);
+    if (cell.x < size.x && cell.y < size.y) {
+        textureStore(life_storage, cell, next_state(cell, size));
+    }
+}
@@ -0,0 +1,38 @@
+fn hash(cell: vec2<i32>) -> f32 {
+    var n = u32(cell.x) * 1973u + u32(cell.y) * 9277u + 26699u;
+    n = (n << 13u) ^ n;
+    n = n * (n * n * 15731u + 789221u) + 1376312589u;
+    return f32(n & 0x7fffffffu) / f32(0x7fffffff);
+}
+
+fn is_alive(cell: vec2<i32>, size: vec2<i32>) -> i32 {
+    let wrapped = (cell % size + size) % size;
+    return i32(textureLoad(life_texture, wrapped, 0).r > 0.5);
+}
+
+fn next_state(cell: vec2<i32>, size: vec2<i32>) -> vec4<f32> {
+    let previous = textureLoad(life_texture, cell, 0);
+
+    if (RANDOM_SEED && frame_u.frame == 0u) {
+        let alive = f32(hash(cell) > 0.7);
+        return vec4<f32>(alive, alive, 0.0, 1.0);
+    }
+
+    var neighbours = 0;
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            if (dx != 0 || dy != 0) {
+                neighbours += is_alive(cell + vec2<i32>(dx, dy), size);
+            }
+        }
+    }
+
+    var alive = neighbours == 3 || (neighbours == 2 && is_alive(cell, size) == 1);
+    if ((frame_u.buttons & 1u) != 0u) {
+        let pointer = frame_u.cursor / max(frame_u.resolution, vec2<f32>(1.0)) * vec2<f32>(size);
+        alive = alive || distance(vec2<f32>(cell) + 0.5, pointer) < 3.0;
+    }
+
+    let state = f32(alive);
+    return vec4<f32>(state, max(state, previous.g * 0.93), 0.0, 1.0);
+}
@@ -0,0 +1,7 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let cell = textureSample(life_texture, life_sampler, in.uv);
+    let background = vec3<f32>(0.05, 0.062, 0.08);
+    let trail = mix(background, vec3<f32>(0.1, 0.3, 0.6), cell.g);
+    return vec4<f32>(mix(trail, vec3<f32>(0.95, 0.85, 0.4), cell.r), 1.0);
+}
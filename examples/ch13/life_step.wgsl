@@ -0,0 +1,4 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return next_state(vec2<i32>(in.position.xy), vec2<i32>(target_u.size));
+}
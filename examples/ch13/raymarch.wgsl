@@ -0,0 +1,54 @@
+fn sd_scene(p: vec3<f32>) -> f32 {
+    let sphere = length(p - vec3<f32>(0.0, 0.2 + 0.3 * sin(frame_u.time), 0.0)) - 0.8;
+    let q = vec2<f32>(length(p.xz) - 1.4, p.y + 0.1);
+    let torus = length(q) - 0.2;
+    let plane = p.y + 0.8;
+    return min(min(sphere, torus), plane);
+}
+
+fn normal(p: vec3<f32>) -> vec3<f32> {
+    let e = vec2<f32>(0.001, 0.0);
+    return normalize(vec3<f32>(
+        sd_scene(p + e.xyy) - sd_scene(p - e.xyy),
+        sd_scene(p + e.yxy) - sd_scene(p - e.yxy),
+        sd_scene(p + e.yyx) - sd_scene(p - e.yyx),
+    ));
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let aspect = frame_u.resolution.x / max(frame_u.resolution.y, 1.0);
+    let ndc = vec2<f32>((in.uv.x * 2.0 - 1.0) * aspect, 1.0 - in.uv.y * 2.0);
+
+    var angle = 0.3 * frame_u.time;
+    if ((frame_u.buttons & 1u) != 0u) {
+        angle = 6.2832 * frame_u.cursor.x / max(frame_u.resolution.x, 1.0);
+    }
+    let eye = vec3<f32>(4.0 * sin(angle), 1.5, 4.0 * cos(angle));
+    let forward = normalize(-eye);
+    let side = normalize(cross(forward, vec3<f32>(0.0, 1.0, 0.0)));
+    let up = cross(side, forward);
+    let dir = normalize(forward * 1.8 + side * ndc.x + up * ndc.y);
+
+    var t = 0.0;
+    for (var i = 0; i < 96; i++) {
+        let d = sd_scene(eye + dir * t);
+        if (d < 0.001 || t > 20.0) {
+            break;
+        }
+        t += d;
+    }
+
+    let sky = mix(vec3<f32>(0.05, 0.062, 0.08), vec3<f32>(0.3, 0.4, 0.6), ndc.y * 0.5 + 0.5);
+    if (t > 20.0) {
+        return vec4<f32>(sky, 1.0);
+    }
+
+    let p = eye + dir * t;
+    let n = normal(p);
+    let light = normalize(vec3<f32>(0.6, 0.8, 0.3));
+    let diffuse = max(dot(n, light), 0.0);
+    let specular = pow(max(dot(reflect(-light, n), -dir), 0.0), 30.0);
+    let color = vec3<f32>(1.0, 0.6, 0.2) * (0.1 + diffuse) + vec3<f32>(specular);
+    return vec4<f32>(mix(color, sky, 1.0 - exp(-0.02 * t * t)), 1.0);
+}
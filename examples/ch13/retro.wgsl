@@ -0,0 +1,10 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(scene_texture, scene_sampler, in.target_uv).rgb;
+    let levels = 6.0;
+    let quantized = floor(color * levels + 0.5) / levels;
+    let scanline = 0.85 + 0.15 * sin(in.position.y * 3.14159);
+    let edge = in.uv * (1.0 - in.uv);
+    let vignette = clamp(pow(edge.x * edge.y * 16.0, 0.25), 0.0, 1.0);
+    return vec4<f32>(quantized * scanline * vignette, 1.0);
+}
@@ -0,0 +1,6 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let horizon = vec3<f32>(0.55, 0.62, 0.7);
+    let zenith = vec3<f32>(0.08, 0.16, 0.32);
+    return vec4<f32>(mix(horizon, zenith, smoothstep(0.0, 1.0, 1.0 - in.uv.y)), 1.0);
+}
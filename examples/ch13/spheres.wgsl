@@ -0,0 +1,34 @@
+fn hit_sphere(eye: vec3<f32>, dir: vec3<f32>, center: vec3<f32>, radius: f32) -> f32 {
+    let oc = eye - center;
+    let b = dot(oc, dir);
+    let disc = b * b - dot(oc, oc) + radius * radius;
+    if (disc < 0.0) {
+        return -1.0;
+    }
+    return -b - sqrt(disc);
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let aspect = frame_u.resolution.x / max(frame_u.resolution.y, 1.0);
+    let ndc = vec2<f32>((in.uv.x * 2.0 - 1.0) * aspect, 1.0 - in.uv.y * 2.0);
+    let eye = vec3<f32>(0.0, 0.0, 5.0);
+    let dir = normalize(vec3<f32>(ndc, -2.5));
+
+    var nearest = 1e9;
+    var color = vec3<f32>(0.05, 0.062, 0.08);
+    for (var i = 0; i < 7; i++) {
+        let phase = f32(i) * 0.8976 + 0.5 * frame_u.time;
+        let center = vec3<f32>(1.6 * cos(phase), 0.6 * sin(2.0 * phase), 1.6 * sin(phase));
+        let t = hit_sphere(eye, dir, center, 0.5);
+        if (t > 0.0 && t < nearest) {
+            nearest = t;
+            let n = normalize(eye + dir * t - center);
+            let base = 0.5 + 0.5 * cos(vec3<f32>(0.0, 2.0, 4.0) + f32(i));
+            let light = normalize(vec3<f32>(-0.5, 0.7, 0.6));
+            color = base * (0.15 + max(dot(n, light), 0.0))
+                + vec3<f32>(pow(max(dot(reflect(-light, n), -dir), 0.0), 40.0));
+        }
+    }
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,18 @@
+struct Output {
+    @builtin(position) @invariant position: vec4<f32>,
+    @location(0) v_position: vec4<f32>,
+    @location(1) v_normal: vec4<f32>,
+    @location(2) v_color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(in: VertexNC) -> Output {
+    let position: vec4<f32> = model_u.points * in.position;
+
+    var output: Output;
+    output.position = camera_u.view_project * position;
+    output.v_position = position;
+    output.v_normal = model_u.normals * in.normal;
+    output.v_color = in.color;
+    return output;
+}
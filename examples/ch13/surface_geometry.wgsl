@@ -0,0 +1,7 @@
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    // Both sides are visible, occlusion is gathered above the side facing the camera
+    let normal = normalize(in.v_normal.xyz);
+    let facing = select(-normal, normal, dot(normal, camera_u.eye.xyz - in.v_position.xyz) >= 0.0);
+    return ssao_geometry(in.v_position.xyz, facing);
+}
@@ -0,0 +1,5 @@
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    ambient_occlusion = occlusion(in.position);
+    return two_side_color(in.v_position, in.v_normal, in.v_color.xyz);
+}
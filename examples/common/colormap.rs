@@ -33,7 +33,7 @@ impl Colormap {
         Self::new(&colormap)
     }
 
-    pub fn interpolator(&self, min_max: (f32, f32)) -> ColormapInterpolator {
+    pub fn interpolator(&self, min_max: (f32, f32)) -> ColormapInterpolator<'_> {
         ColormapInterpolator { colormap: self, min_max }
     }
 
@@ -2,12 +2,15 @@
 
 use core::{cell::RefCell, str::FromStr, fmt::Debug};
 
+use webgpu_book::{PipelineConfiguration, PostProcessing, Poster, Recording, RenderConfiguration, RenderPassConfiguration};
+
 pub use vertex::*;
 
 pub mod colormap;
 pub mod vertex_data;
 pub mod functions;
 pub mod light;
+pub mod material;
 pub mod surface_data;
 pub mod mvp;
 mod vertex;
@@ -17,9 +20,16 @@ pub struct CmdArgs;
 thread_local!(static ARGS: RefCell<Vec<String>> = RefCell::new(std::env::args().skip(1).rev().collect()));
 
 impl CmdArgs {
+    // Options are left for `has_option` and `get_option`, wherever they are on the command line
     #[must_use]
     pub fn next(default: &str) -> String {
-        ARGS.with(|cell| cell.borrow_mut().pop().unwrap_or(default.to_owned()))
+        ARGS.with(|cell| {
+            let mut args = cell.borrow_mut();
+            match args.last() {
+                Some(value) if !value.starts_with("--") => args.pop().unwrap_or_default(),
+                _ => default.to_owned(),
+            }
+        })
     }
 
     #[must_use]
@@ -65,6 +75,44 @@ impl CmdArgs {
         })
     }
 
+    pub fn read_export(render: &mut RenderConfiguration) {
+        if let Some(directory) = CmdArgs::get_option::<String>("--record") {
+            let frames = CmdArgs::get_option("--frames").unwrap_or(120);
+            let mut recording = Recording::new(directory.as_str(), frames)
+                .with_fps(CmdArgs::get_option("--fps").unwrap_or(30))
+                .with_gif(CmdArgs::has_option("--gif"));
+            if let Some((width, height)) = Self::read_size() {
+                recording = recording.with_size(width, height);
+            }
+            render.record(recording);
+        } else if let Some(filename) = CmdArgs::get_option::<String>("--poster") {
+            let (width, height) = Self::read_size().unwrap_or((7680, 4320));
+            let mut poster = Poster::new(filename.as_str(), width, height)
+                .with_supersampling(CmdArgs::get_option("--supersample").unwrap_or(1));
+            if let Some(tile_size) = CmdArgs::get_option("--tile") {
+                poster = poster.with_tile_size(tile_size);
+            }
+            render.poster(poster);
+        }
+    }
+
+    pub fn read_post(render: &mut RenderConfiguration) {
+        if CmdArgs::has_option("--post") {
+            render.with_post_processing(PostProcessing::new()
+                .with_exposure(CmdArgs::get_option("--exposure").unwrap_or(1.0))
+                .with_bloom(CmdArgs::get_option("--bloom").unwrap_or(0.8), 0.6)
+                .with_fxaa(true)
+                .with_vignette(0.4));
+        }
+    }
+
+    fn read_size() -> Option<(u32, u32)> {
+        CmdArgs::get_option::<String>("--size").map(|size| {
+            let (width, height) = size.split_once('x').expect("--size expects WIDTHxHEIGHT");
+            (width.parse().expect("valid width"), height.parse().expect("valid height"))
+        })
+    }
+
     pub(crate) fn is(expected: &str) -> bool {
         ARGS.with(|cell| {
             let mut args = cell.borrow_mut();
@@ -76,3 +124,25 @@ impl CmdArgs {
         })
     }
 }
+
+// RunExport
+
+// Runs in a window, or records frames or a poster when `--record` or `--poster` is given
+pub trait RunExport {
+    fn run_export(self, title: &str) -> !;
+}
+
+impl RunExport for RenderPassConfiguration {
+    fn run_export(self, title: &str) -> ! {
+        let mut render = RenderConfiguration::new();
+        CmdArgs::read_export(&mut render);
+        render.add_pass(self);
+        render.run_title(title)
+    }
+}
+
+impl RunExport for PipelineConfiguration {
+    fn run_export(self, title: &str) -> ! {
+        RenderPassConfiguration::new(vec![self]).run_export(title)
+    }
+}
@@ -1,9 +1,32 @@
-fn diffuse(dotNL: f32) -> f32 {
-    return light_u.diffuse_intensity * max(dotNL, 0.0);
+// Scales the ambient term, fragment shaders with an occlusion texture set it before lighting
+var<private> ambient_occlusion: f32 = 1.0;
+
+const LIGHT_DIRECTIONAL: u32 = 0u;
+const LIGHT_POINT: u32 = 1u;
+const LIGHT_SPOT: u32 = 2u;
+
+fn diffuse(light: Light, dotNL: f32) -> f32 {
+    return light.diffuse_intensity * max(dotNL, 0.0);
 }
 
-fn specular(dotNH: f32) -> f32 {
-    return light_u.specular_intensity * pow(max(dotNH, 0.0), light_u.specular_shininess);
+fn specular(light: Light, dotNH: f32, shininess: f32) -> f32 {
+    return light.specular_intensity * pow(max(dotNH, 0.0), shininess);
+}
+
+// Direction towards the light in xyz, attenuation and spot cone falloff in w
+fn incidence(light: Light, position: vec3<f32>) -> vec4<f32> {
+    if (light.kind == LIGHT_DIRECTIONAL) {
+        return vec4(-normalize(light.direction.xyz), 1.0);
+    }
+    let to_light = light.position.xyz - position;
+    let dist = length(to_light);
+    let L = to_light / dist;
+    var strength = 1.0 / (light.constant + light.linear * dist + light.quadratic * dist * dist);
+    if (light.kind == LIGHT_SPOT) {
+        let cos_angle = dot(-L, normalize(light.direction.xyz));
+        strength *= smoothstep(light.outer_cos, light.inner_cos, cos_angle);
+    }
+    return vec4(L, strength);
 }
 
 fn color(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>) -> vec4<f32> {
@@ -11,22 +34,35 @@ fn color(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>) -> vec4<f32>
 }
 
 fn color_both(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>, back: f32) -> vec4<f32> {
+    return shade(position, normal, color, back, light_u.specular_shininess);
+}
+
+fn shade(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>, back: f32, shininess: f32) -> vec4<f32> {
     let N: vec3<f32> = normalize(normal.xyz);
-    let L: vec3<f32> = normalize(light_u.position.xyz - position.xyz);
     let V: vec3<f32> = normalize(camera_u.eye.xyz - position.xyz);
-    let H: vec3<f32> = normalize(L + V);
-    let dotNL = dot(N, L);
-    let dotNH = dot(N, H);
 
+    var lit = vec3(0.0);
+    var highlight = vec3(0.0);
+    for (var i = 0u; i < light_u.count; i++) {
+        let light = light_u.lights[i];
+        let incidence = incidence(light, position.xyz);
+        let L: vec3<f32> = incidence.xyz;
+        let H: vec3<f32> = normalize(L + V);
+        let dotNL = dot(N, L);
+        let dotNH = dot(N, H);
+
+        var diffuse: f32 = diffuse(light, dotNL);
+        var specular: f32 = specular(light, dotNH, shininess);
 
-    var diffuse: f32 = diffuse(dotNL);
-    var specular: f32 = specular(dotNH);
+        if (back != 0.0) {
+            diffuse += back * diffuse(light, -dotNL);
+            specular += back * specular(light, -dotNH, shininess);
+        }
 
-    if (back != 0.0) {
-        diffuse += back * diffuse(-dotNL);
-        specular += back * specular(-dotNH);
+        lit += light.color.xyz * diffuse * incidence.w;
+        highlight += light.specular_color.xyz * specular * incidence.w;
     }
 
-    let ambient = light_u.ambient_intensity;
-    return vec4(color * (ambient + diffuse) + light_u.specular_color.xyz * specular, 1.0);
+    let ambient = light_u.ambient_intensity * ambient_occlusion;
+    return vec4(color * (ambient + lit) + highlight, 1.0);
 }
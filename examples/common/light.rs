@@ -1,16 +1,14 @@
 #![allow(clippy::extra_unused_type_parameters)]
 
-use core::time::Duration;
-
 use bytemuck::{Pod, Zeroable};
-use cgmath::{Angle, Matrix, Matrix3, Matrix4, Point3, point3, Rad, SquareMatrix, Vector3, Vector4, Zero};
-use winit::event::DeviceEvent;
+use cgmath::{Angle, InnerSpace, Matrix, Matrix3, Matrix4, Point3, point3, Rad, SquareMatrix, vec3, Vector3, Vector4, Zero};
 
-use webgpu_book::{Configurator, Content, func_box, PipelineConfiguration, To, Uniform, UniformInfo, VertexBufferInfo};
+use webgpu_book::{CameraKey, CameraPath, CameraTimeline, Configurator, Content, Easing, FrameTime, func_box, InputEvent, InputState, OrbitCamera, PipelineConfiguration, RenderConfiguration, Tile, To, Uniform, UniformInfo, UniformsConfiguration, VertexBufferInfo};
 use webgpu_book::boxed::FuncBox;
-use webgpu_book::transforms::{create_projection, create_rotation, invert};
+use webgpu_book::transforms::{create_rotation, Frustum, invert, Projection};
 
 use super::{CmdArgs, VertexN};
+use super::material::Material;
 use super::surface_data::Edges;
 use super::surface_data::Triangles;
 
@@ -22,13 +20,26 @@ pub struct OglCamera {
     eye: Point3<f32>,
     look_at: Point3<f32>,
     up: Vector3<f32>,
-    fovy: Rad<f32>,
-    projection: Matrix4<f32>,
+    projection: Projection,
+    aspect: f32,
+    matrix: Matrix4<f32>,
+    tile: Matrix4<f32>,
 }
 
 impl OglCamera {
     pub fn new(eye: Point3<f32>, look_at: Point3<f32>, up: Vector3<f32>, fovy: Rad<f32>) -> Self {
-        Self { eye, look_at, up, fovy, projection: create_projection(1.0, fovy) }
+        let projection = Projection::from_fovy(fovy);
+        Self { eye, look_at, up, projection, aspect: 1.0, matrix: projection.matrix(1.0), tile: Matrix4::identity() }
+    }
+
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self.matrix = projection.matrix(self.aspect);
+        self
+    }
+
+    pub fn projection_settings(&self) -> Projection {
+        self.projection
     }
 
     #[must_use]
@@ -38,7 +49,7 @@ impl OglCamera {
 
     #[must_use]
     pub fn projection(&self) -> Matrix4<f32> {
-        self.projection
+        self.tile * self.matrix
     }
 
     #[must_use]
@@ -47,8 +58,42 @@ impl OglCamera {
     }
 
     pub fn resize(&mut self, width: u32, height: u32) -> Matrix4<f32> {
-        self.projection = create_projection(width as f32 / height as f32, self.fovy);
-        self.projection
+        self.aspect = width as f32 / height as f32;
+        self.matrix = self.projection.matrix(self.aspect);
+        self.matrix
+    }
+
+    pub fn key(&self) -> CameraKey {
+        CameraKey::new(self.eye, self.look_at, self.up, self.projection.fovy())
+    }
+
+    pub fn set_key(&mut self, key: &CameraKey) {
+        self.eye = key.eye;
+        self.look_at = key.look_at;
+        self.up = key.up;
+        if key.fovy != self.projection.fovy() {
+            self.projection = self.projection.with_fovy(key.fovy);
+            self.matrix = self.projection.matrix(self.aspect);
+        }
+    }
+
+    pub fn set_tile(&mut self, tile: &Tile) {
+        self.tile = tile.matrix();
+    }
+
+    pub fn orbit(&self) -> OrbitCamera {
+        OrbitCamera::new(self.eye, self.look_at, self.up).with_projection(self.projection)
+    }
+
+    pub fn follow(&mut self, orbit: &OrbitCamera) {
+        self.eye = orbit.eye();
+        self.look_at = orbit.target();
+        self.up = orbit.up();
+        // An orthographic orbit zooms by its height
+        if let Frustum::Orthographic { .. } = self.projection.frustum() {
+            self.projection = orbit.projection();
+            self.matrix = self.projection.matrix(self.aspect);
+        }
     }
 
     pub fn transform(&mut self, transform: Matrix3<f32>) {
@@ -64,30 +109,137 @@ impl OglCamera {
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[must_use]
-pub struct LightUniform {
+pub struct Light {
     position: [f32; 4],
+    direction: [f32; 4],
+    color: [f32; 4],
     specular_color: [f32; 4],
-    ambient_intensity: f32,
+    kind: u32,
+    constant: f32,
+    linear: f32,
+    quadratic: f32,
+    inner_cos: f32,
+    outer_cos: f32,
     diffuse_intensity: f32,
     specular_intensity: f32,
+}
+
+impl Light {
+    const DIRECTIONAL: u32 = 0;
+    const POINT: u32 = 1;
+    const SPOT: u32 = 2;
+
+    fn new(kind: u32, color: Point3<f32>) -> Self {
+        Self {
+            position: [0.0, 0.0, 0.0, 1.0],
+            direction: [0.0, -1.0, 0.0, 0.0],
+            color: color.to_homogeneous().into(),
+            specular_color: color.to_homogeneous().into(),
+            kind,
+            constant: 1.0,
+            linear: 0.0,
+            quadratic: 0.0,
+            inner_cos: 1.0,
+            outer_cos: 1.0,
+            diffuse_intensity: 1.0,
+            specular_intensity: 1.0,
+        }
+    }
+
+    // Light travelling along the direction, without attenuation
+    pub fn directional(direction: Vector3<f32>, color: Point3<f32>) -> Self {
+        Self {
+            direction: direction.normalize().extend(0.0).into(),
+            ..Self::new(Self::DIRECTIONAL, color)
+        }
+    }
+
+    pub fn point(position: Point3<f32>, color: Point3<f32>) -> Self {
+        Self {
+            position: position.to_homogeneous().into(),
+            ..Self::new(Self::POINT, color)
+        }
+    }
+
+    // Point light restricted to a cone, fading out between the inner and the outer angle
+    pub fn spot(position: Point3<f32>, direction: Vector3<f32>, color: Point3<f32>, inner: Rad<f32>, outer: Rad<f32>) -> Self {
+        Self {
+            position: position.to_homogeneous().into(),
+            direction: direction.normalize().extend(0.0).into(),
+            inner_cos: inner.cos(),
+            outer_cos: outer.cos(),
+            ..Self::new(Self::SPOT, color)
+        }
+    }
+
+    pub fn with_attenuation(mut self, constant: f32, linear: f32, quadratic: f32) -> Self {
+        self.constant = constant;
+        self.linear = linear;
+        self.quadratic = quadratic;
+        self
+    }
+
+    pub fn with_intensity(mut self, diffuse_intensity: f32, specular_intensity: f32) -> Self {
+        self.diffuse_intensity = diffuse_intensity;
+        self.specular_intensity = specular_intensity;
+        self
+    }
+
+    pub fn with_specular_color(mut self, specular_color: Point3<f32>) -> Self {
+        self.specular_color = specular_color.to_homogeneous().into();
+        self
+    }
+}
+
+// LightUniform
+
+// A macro, as the WGSL array length is spliced into a string at compile time
+macro_rules! max_lights {
+    () => { 4 };
+}
+
+pub const MAX_LIGHTS: usize = max_lights!();
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[must_use]
+pub struct LightUniform {
+    lights: [Light; MAX_LIGHTS],
+    count: u32,
+    ambient_intensity: f32,
     specular_shininess: f32,
+    padding: [u8; 4],
 }
 
 impl UniformInfo for LightUniform {
     const STRUCT_NAME: &'static str = "LightUniform";
     const BINDING_NAME: &'static str = "light_u";
     const ATTRIBUTES: &'static [(&'static str, &'static str)] = &[
-        ("position", "vec4<f32>"),
-        ("specular_color", "vec4<f32>"),
+        ("lights", concat!("array<Light, ", max_lights!(), ">")),
+        ("count", "u32"),
         ("ambient_intensity", "f32"),
-        ("diffuse_intensity", "f32"),
-        ("specular_intensity", "f32"),
         ("specular_shininess", "f32"),
     ];
     const FUNCTIONS: &'static str = include_str!("light-functions.wgsl");
+    const TYPES: &'static str = "struct Light {
+    position: vec4<f32>,
+    direction: vec4<f32>,
+    color: vec4<f32>,
+    specular_color: vec4<f32>,
+    kind: u32,
+    constant: f32,
+    linear: f32,
+    quadratic: f32,
+    inner_cos: f32,
+    outer_cos: f32,
+    diffuse_intensity: f32,
+    specular_intensity: f32,
+}
+";
 }
 
 impl LightUniform {
+    // Single white point light
     pub fn new(
         position: Point3<f32>,
         specular_color: Point3<f32>,
@@ -96,14 +248,23 @@ impl LightUniform {
         specular_intensity: f32,
         specular_shininess: f32,
     ) -> Self {
-        Self {
-            position: position.to_homogeneous().into(),
-            specular_color: specular_color.to_homogeneous().into(),
+        let light = Light::point(position, point3(1.0, 1.0, 1.0))
+            .with_specular_color(specular_color)
+            .with_intensity(diffuse_intensity, specular_intensity);
+        Self::lights(&[light], ambient_intensity, specular_shininess)
+    }
+
+    pub fn lights(lights: &[Light], ambient_intensity: f32, specular_shininess: f32) -> Self {
+        assert!(lights.len() <= MAX_LIGHTS, "At most {MAX_LIGHTS} lights, got {}", lights.len());
+        let mut uniform = Self {
+            lights: [Light::new(Light::DIRECTIONAL, point3(0.0, 0.0, 0.0)); MAX_LIGHTS],
+            count: lights.len() as u32,
             ambient_intensity,
-            diffuse_intensity,
-            specular_intensity,
             specular_shininess,
-        }
+            padding: [0; 4],
+        };
+        uniform.lights[..lights.len()].copy_from_slice(lights);
+        uniform
     }
 
     pub fn example() -> LightUniform {
@@ -113,6 +274,41 @@ impl LightUniform {
             0.1, 1.0, 1.0, 30.0,
         )
     }
+
+    // Warm key light, cool fill from the other side and a rim light from behind the subject
+    pub fn three_point() -> LightUniform {
+        LightUniform::lights(&[
+            Light::point(point3(4.0, 4.0, 3.0), point3(1.0, 0.95, 0.85))
+                .with_intensity(0.9, 0.8),
+            Light::directional(vec3(0.9, -0.2, -0.45), point3(0.6, 0.7, 0.9))
+                .with_intensity(0.35, 0.0),
+            Light::directional(vec3(0.4, -0.5, 0.8), point3(1.0, 1.0, 1.0))
+                .with_intensity(0.7, 1.0),
+        ], 0.08, 30.0)
+    }
+
+    pub fn spots() -> LightUniform {
+        let spot = |position: Point3<f32>, color| Light::spot(
+            position, point3(0.0, 0.0, 0.0) - position, color, Rad::full_turn() / 36.0, Rad::full_turn() / 18.0,
+        ).with_attenuation(1.0, 0.02, 0.01);
+        LightUniform::lights(&[
+            spot(point3(0.0, 5.0, 3.0), point3(1.0, 0.9, 0.8)),
+            spot(point3(4.0, 1.0, 2.0), point3(0.4, 0.6, 1.0)),
+            Light::point(point3(-3.0, 2.0, 3.0), point3(0.5, 0.5, 0.5))
+                .with_attenuation(1.0, 0.1, 0.05),
+        ], 0.05, 30.0)
+    }
+
+    // Lighting rig named by the `--lights` option
+    #[must_use]
+    pub fn read_args() -> Option<LightUniform> {
+        CmdArgs::get_option::<String>("--lights").map(|name| match name.as_str() {
+            "example" => Self::example(),
+            "three-point" => Self::three_point(),
+            "spots" => Self::spots(),
+            _ => panic!("--lights: unknown rig '{name}', expected one of [\"example\", \"three-point\", \"spots\"]"),
+        })
+    }
 }
 
 
@@ -124,11 +320,17 @@ impl LightUniform {
 pub struct Model {
     model: Matrix4<f32>,
     rotation: Matrix4<f32>,
+    material: Material,
 }
 
 impl Model {
     pub fn new(model: Matrix4<f32>) -> Self {
-        Self { model, rotation: Matrix4::identity() }
+        Self { model, rotation: Matrix4::identity(), material: Material::example() }
+    }
+
+    pub fn with_material(mut self, material: Material) -> Self {
+        self.material = material;
+        self
     }
 }
 
@@ -224,25 +426,44 @@ impl LightExamples {
 
     pub fn models<const ML: usize, AU>(
         aux: AU,
-        models: [Matrix4<f32>; ML],
+        models: [Model; ML],
         instances: bool
     ) -> Configurator<PipelineConfiguration> where AU: UniformInfo {
-        let camera = OglCamera::new(
-            point3(3.0, 1.5, 4.0),
-            point3(0.0, 0.0, 0.0),
-            Vector3::unit_y(),
-            Rad::full_turn() / 5.0,
-        );
         Self::configurator::<ML, AU, MergedVPUniform>(
-            models.map(Model::new),
+            models,
             instances,
-            camera,
+            Self::camera(),
             LightUniform::example(),
             aux,
             Box::new(SawController { animation_speed: 1.0 }),
         )
     }
 
+    // Far enough to zoom out of the merged and instanced scenes
+    pub fn camera() -> OglCamera {
+        let fovy = Rad::full_turn() / 5.0;
+        OglCamera::new(
+            point3(3.0, 1.5, 4.0),
+            point3(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+            fovy,
+        ).with_projection(Projection::perspective(fovy).with_far(100.0))
+    }
+
+    // The default camera with `--far` and `--reverse-z`, reverse-Z without a far plane is infinite
+    pub fn read_camera() -> OglCamera {
+        let camera = Self::camera();
+        let reverse_z = CmdArgs::has_option("--reverse-z");
+        let far = CmdArgs::get_option::<f32>("--far");
+        let mut projection = Projection::perspective(camera.key().fovy).with_reverse_z(reverse_z);
+        projection = match far {
+            Some(far) => projection.with_far(far),
+            None if reverse_z => projection.with_infinite_far(),
+            None => projection.with_far(100.0),
+        };
+        camera.with_projection(projection)
+    }
+
     pub fn configurator<const ML: usize, AU, CU>(
         models: [Model; ML],
         instances: bool,
@@ -256,6 +477,7 @@ impl LightExamples {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn configure<const ML: usize, AU: UniformInfo, CU: UniformInfo>(
         mut pipeline: PipelineConfiguration,
         models: [Model; ML],
@@ -265,9 +487,98 @@ impl LightExamples {
         aux: AU,
         controller: Box<dyn for<'a> Content<&'a mut UniformsData<ML, AU>>>
     ) -> PipelineConfiguration where OglCamera: To<CU> {
-        let uniforms = pipeline.uniforms();
+        let timeline = Self::viewpoints(&camera);
+        let controller = Self::camera_controller(&camera, timeline, controller);
+        let unif = Self::uniforms::<ML, AU, CU>(pipeline.uniforms(), models, instances, camera, light, aux, controller);
+        pipeline.add_listener(Box::new(unif));
+        pipeline
+    }
+
+    pub fn shared<AU: UniformInfo>(render: &mut RenderConfiguration, light: LightUniform, material: Material, aux: AU) {
+        let camera = Self::camera();
+        let timeline = Self::viewpoints(&camera);
+        Self::shared_camera(render, camera, timeline, light, material, aux);
+    }
+
+    pub fn shared_camera<AU: UniformInfo>(
+        render: &mut RenderConfiguration,
+        camera: OglCamera,
+        timeline: CameraTimeline,
+        light: LightUniform,
+        material: Material,
+        aux: AU,
+    ) {
+        let controller = Self::camera_controller(&camera, timeline, Box::new(SawController { animation_speed: 1.0 }));
+        let unif = Self::uniforms::<1, AU, MergedVPUniform>(
+            render.uniforms(),
+            [Model::new(Matrix4::identity()).with_material(material)],
+            false,
+            camera,
+            light,
+            aux,
+            controller,
+        );
+        render.add_listener(Box::new(unif));
+    }
+
+    fn camera_controller<const ML: usize, AU: 'static>(
+        camera: &OglCamera,
+        timeline: CameraTimeline,
+        controller: Box<dyn for<'a> Content<&'a mut UniformsData<ML, AU>>>,
+    ) -> Box<dyn for<'a> Content<&'a mut UniformsData<ML, AU>>> {
+        Box::new(CameraController { orbit: camera.orbit(), timeline, controller })
+    }
+
+    // Keys 1 to 4 move to the initial, front, side and top views of the shared scenes
+    fn viewpoints(camera: &OglCamera) -> CameraTimeline {
+        let key = camera.key();
+        let distance = (key.eye - key.look_at).magnitude();
+        let view = |direction: Vector3<f32>, up| CameraKey { eye: key.look_at + direction * distance, up, ..key };
+        CameraTimeline::new().with_viewpoints([
+            key,
+            view(Vector3::unit_z(), Vector3::unit_y()),
+            view(Vector3::unit_x(), Vector3::unit_y()),
+            view(Vector3::unit_y(), -Vector3::unit_z()),
+        ])
+    }
 
-        let unif = Uniforms {
+    // Circles the scene while rising and falling, then closes in and back out
+    fn tour(camera: &OglCamera) -> CameraTimeline {
+        let key = camera.key();
+        let offset = key.eye - key.look_at;
+        let around = |turn: f32, height: f32, scale: f32| {
+            let rotation = Matrix3::from_angle_y(Rad::full_turn() * turn);
+            CameraKey { eye: key.look_at + rotation * (offset * scale) + Vector3::unit_y() * height, ..key }
+        };
+        let path = CameraPath::new()
+            .with_key(0.0, key)
+            .with_key(4.0, around(0.25, 1.5, 1.0))
+            .with_key(8.0, around(0.5, -1.0, 0.6))
+            .with_key(12.0, around(0.75, 0.5, 1.2))
+            .with_key(16.0, key)
+            .with_easing(Easing::Linear)
+            .with_loop(true);
+        Self::viewpoints(camera).with_path(path)
+    }
+
+    // The `--tour` option plays a looping fly-through of the shared scene, 0 replays it
+    pub fn read_timeline(camera: &OglCamera) -> CameraTimeline {
+        if CmdArgs::has_option("--tour") { Self::tour(camera) } else { Self::viewpoints(camera) }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn uniforms<const ML: usize, AU: UniformInfo, CU: UniformInfo>(
+        uniforms: &mut UniformsConfiguration,
+        models: [Model; ML],
+        instances: bool,
+        camera: OglCamera,
+        light: LightUniform,
+        aux: AU,
+        controller: Box<dyn for<'a> Content<&'a mut UniformsData<ML, AU>>>
+    ) -> Uniforms<ML, AU> where OglCamera: To<CU> {
+        // Materials follow the models at binding 1, indexed the same way
+        let materials = models.clone().map(|model| model.material);
+        Uniforms {
             data: UniformsData {
                 models: (
                     if instances {
@@ -277,11 +588,22 @@ impl LightExamples {
                             .instance_array::<ModelUniforms>()
                     } else {
                         uniforms
-                            .variants((0..ML).map(|i| vec![i]).collect())
+                            .variants((0..ML).map(|i| vec![i, i]).collect())
                             .add("Models", models, wgpu::ShaderStages::VERTEX)
                             .bindings_array::<ModelUniforms>()
                     }
                 ),
+                materials: (
+                    if instances {
+                        uniforms
+                            .add("Materials", materials, wgpu::ShaderStages::FRAGMENT)
+                            .instance_array::<Material>()
+                    } else {
+                        uniforms
+                            .add("Materials", materials, wgpu::ShaderStages::FRAGMENT)
+                            .bindings_array::<Material>()
+                    }
+                ),
                 camera: uniforms.add("Camera", camera, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT)
                     .value::<CU>(),
                 light: uniforms.add("Light", light, wgpu::ShaderStages::FRAGMENT)
@@ -290,13 +612,11 @@ impl LightExamples {
                     .value::<AU>(),
             },
             controller,
-        };
-        pipeline.add_listener(Box::new(unif));
-        pipeline
+        }
     }
 
     pub fn aux<AU: UniformInfo>(aux: AU) -> Configurator<PipelineConfiguration> {
-        Self::models(aux, [Matrix4::identity()], true)
+        Self::models(aux, [Model::new(Matrix4::identity())], true)
     }
 }
 
@@ -311,6 +631,7 @@ pub struct Uniforms<const ML: usize, A> {
 
 pub struct UniformsData<const ML: usize, A> {
     pub(crate) models: Uniform<[Model; ML]>,
+    pub(crate) materials: Uniform<[Material; ML]>,
     pub(crate) camera: Uniform<OglCamera>,
     pub(crate) light: Uniform<LightUniform>,
     pub(crate) aux: Uniform<A>,
@@ -322,13 +643,17 @@ impl<const ML: usize, AU: Pod> Content<()> for Uniforms<ML, AU> {
         self.controller.resize(&mut self.data, width, height);
     }
 
-    fn update(&mut self, _context: (), dt: Duration) {
-        self.controller.update(&mut self.data, dt);
+    fn update(&mut self, _context: (), time: &FrameTime, input: &InputState) {
+        self.controller.update(&mut self.data, time, input);
     }
 
-    fn input(&mut self, _context: (), event: &DeviceEvent) {
+    fn input(&mut self, _context: (), event: &InputEvent) {
         self.controller.input(&mut self.data, event);
     }
+
+    fn tile(&mut self, _context: (), tile: &Tile) {
+        self.data.camera.as_mut().set_tile(tile);
+    }
 }
 
 
@@ -381,8 +706,8 @@ struct SawController<const ML: usize> {
 }
 
 impl<const ML: usize, AU> Content<&mut UniformsData<ML, AU>> for SawController<ML> {
-    fn update(&mut self, context: &mut UniformsData<ML, AU>, dt: Duration) {
-        let time = self.animation_speed * dt.as_secs_f32();
+    fn update(&mut self, context: &mut UniformsData<ML, AU>, time: &FrameTime, _input: &InputState) {
+        let time = self.animation_speed * time.total_secs();
         let (angle_sin, angle_cos) = (Rad::full_turn() * time / 5.0).sin_cos();
         let rotation = create_rotation([
             Rad::full_turn() * angle_sin / 20.0,
@@ -394,13 +719,50 @@ impl<const ML: usize, AU> Content<&mut UniformsData<ML, AU>> for SawController<M
             model.rotation = rotation;
         }
 
+        // Rigs with several lights stay put, only a single light is animated
         let mut light = context.light.as_mut();
-        light.ambient_intensity = Self::saw(time / 4.0);
-        light.position = (point3(angle_sin, angle_cos, 0.0) * 10.0).to_homogeneous().into();
+        if light.count == 1 {
+            light.ambient_intensity = Self::saw(time / 4.0);
+            light.lights[0].position = (point3(angle_sin, angle_cos, 0.0) * 10.0).to_homogeneous().into();
+        }
         // self.camera.as_mut().eye.z = 25.0 + Self::saw(time / 6.0) * 3.0;
     }
 }
 
+// Orbits the camera with the mouse and plays the camera timeline around whatever the wrapped
+// controller animates, moving the camera by hand stops the timeline
+struct CameraController<const ML: usize, AU> {
+    orbit: OrbitCamera,
+    timeline: CameraTimeline,
+    controller: Box<dyn for<'a> Content<&'a mut UniformsData<ML, AU>>>,
+}
+
+impl<const ML: usize, AU> Content<&mut UniformsData<ML, AU>> for CameraController<ML, AU> {
+    fn resize(&mut self, context: &mut UniformsData<ML, AU>, width: u32, height: u32) {
+        self.orbit.resize(width, height);
+        self.controller.resize(context, width, height);
+    }
+
+    fn update(&mut self, context: &mut UniformsData<ML, AU>, time: &FrameTime, input: &InputState) {
+        if let Some(key) = self.timeline.update(time) {
+            context.camera.as_mut().set_key(&key);
+            self.orbit.look(key.eye, key.look_at, key.up);
+        } else if self.orbit.update(time) {
+            context.camera.as_mut().follow(&self.orbit);
+        }
+        self.controller.update(context, time, input);
+    }
+
+    fn input(&mut self, context: &mut UniformsData<ML, AU>, event: &InputEvent) {
+        self.timeline.input(event, context.camera.key());
+        if self.orbit.input(event) {
+            self.timeline.stop();
+            context.camera.as_mut().follow(&self.orbit);
+        }
+        self.controller.input(context, event);
+    }
+}
+
 impl<const ML: usize> SawController<ML> {
     fn saw(time: f32) -> f32 {
         (time % 1.0 - 0.5).abs() * 2.0
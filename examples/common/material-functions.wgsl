@@ -0,0 +1,8 @@
+// `color_both` with the base color, shininess and sides of a model material
+fn color_model(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>, material: Material) -> vec4<f32> {
+    var back: f32;
+    if (material.two_side != 0u) {
+        back = 0.5;
+    }
+    return shade(position, normal, color * material.base_color.rgb, back, material.shininess);
+}
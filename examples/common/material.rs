@@ -0,0 +1,87 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Point3, point3};
+
+use webgpu_book::UniformInfo;
+
+use super::CmdArgs;
+
+// Material
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[must_use]
+pub struct Material {
+    base_color: [f32; 4],
+    emissive: [f32; 4],
+    metallic: f32,
+    roughness: f32,
+    shininess: f32,
+    texture: u32,
+    two_side: u32,
+    padding: [u8; 12],
+}
+
+impl UniformInfo for Material {
+    const STRUCT_NAME: &'static str = "Material";
+    const BINDING_NAME: &'static str = "material_u";
+    const ATTRIBUTES: &'static [(&'static str, &'static str)] = &[
+        ("base_color", "vec4<f32>"),
+        ("emissive", "vec4<f32>"),
+        ("metallic", "f32"),
+        ("roughness", "f32"),
+        ("shininess", "f32"),
+        ("texture", "u32"),
+        ("two_side", "u32"),
+    ];
+    const FUNCTIONS: &'static str = concat!(include_str!("pbr-functions.wgsl"), include_str!("material-functions.wgsl"));
+}
+
+impl Material {
+    pub fn new(base_color: Point3<f32>, metallic: f32, roughness: f32) -> Self {
+        Self {
+            base_color: base_color.to_homogeneous().into(),
+            emissive: [0.0; 4],
+            metallic,
+            roughness,
+            shininess: 30.0,
+            texture: 0,
+            two_side: 0,
+            padding: [0; 12],
+        }
+    }
+
+    pub fn with_emissive(mut self, emissive: Point3<f32>) -> Self {
+        self.emissive = emissive.to_homogeneous().into();
+        self
+    }
+
+    // Blinn-Phong shininess, texture index and sides used by `color_model`
+    pub fn with_shininess(mut self, shininess: f32) -> Self {
+        self.shininess = shininess;
+        self
+    }
+
+    pub fn with_texture(mut self, texture: u32) -> Self {
+        self.texture = texture;
+        self
+    }
+
+    pub fn with_two_side(mut self, two_side: bool) -> Self {
+        self.two_side = u32::from(two_side);
+        self
+    }
+
+    // White dielectric, the base color comes from the vertices
+    pub fn example() -> Self {
+        Self::new(point3(1.0, 1.0, 1.0), 0.0, 0.5)
+    }
+
+    pub fn read_args() -> Self {
+        let example = Self::example();
+        Self::new(
+            point3(1.0, 1.0, 1.0),
+            CmdArgs::get_option("--metallic").unwrap_or(example.metallic),
+            CmdArgs::get_option("--roughness").unwrap_or(example.roughness),
+        )
+    }
+}
@@ -1,11 +1,10 @@
-use core::time::Duration;
 use bytemuck::{Pod, Zeroable};
 
-use cgmath::{Deg, Matrix4, Point3, Rad, SquareMatrix, Vector3};
+use cgmath::{Deg, Matrix4, Point3, point3, Rad, SquareMatrix, Vector3};
 
-use webgpu_book::{Configurator, Content, func_box, PipelineConfiguration, To, typed_box, Uniform, UniformInfo};
+use webgpu_book::{CameraTimeline, Configurator, Content, FlyCamera, FrameTime, func_box, InputEvent, InputState, OrbitCamera, PipelineConfiguration, Tile, To, typed_box, Uniform, UniformInfo};
 use webgpu_book::boxed::FuncBox;
-use webgpu_book::transforms::{create_projection, create_rotation, create_view};
+use webgpu_book::transforms::{create_rotation, create_view, Projection};
 
 
 // Mvp
@@ -14,6 +13,7 @@ struct Mvp {
     model: Matrix4<f32>,
     view: Matrix4<f32>,
     projection: Matrix4<f32>,
+    tile: Matrix4<f32>,
 }
 
 #[repr(C)]
@@ -32,7 +32,7 @@ impl UniformInfo for MvpMatrix {
 
 impl To<MvpMatrix> for Mvp {
     fn to(&self) -> MvpMatrix {
-        MvpMatrix{ matrix: (self.projection * self.view * self.model).into() }
+        MvpMatrix{ matrix: (self.tile * self.projection * self.view * self.model).into() }
     }
 }
 
@@ -42,13 +42,37 @@ impl To<MvpMatrix> for Mvp {
 
 pub struct MvpController<T> {
     mvp: Uniform<Mvp>,
-    fovy: Rad<f32>,
+    projection: Projection,
+    aspect: f32,
     pub(crate) state: T,
 }
 
-impl Content<()> for MvpController<()> {
+// Behaviour of a controller state, the projection and the poster tiles are kept up to date for all of them
+pub trait MvpState: Sized {
+    fn resize(&mut self, _width: u32, _height: u32) {}
+    fn update(_controller: &mut MvpController<Self>, _time: &FrameTime, _input: &InputState) {}
+    fn input(_controller: &mut MvpController<Self>, _event: &InputEvent) {}
+}
+
+impl MvpState for () {}
+
+impl<T: MvpState> Content<()> for MvpController<T> {
     fn resize(&mut self, _content: (), width: u32, height: u32) {
-        self.mvp.as_mut().projection = create_projection(width as f32 / height as f32, self.fovy);
+        self.aspect = width as f32 / height as f32;
+        self.mvp.as_mut().projection = self.projection.matrix(self.aspect);
+        self.state.resize(width, height);
+    }
+
+    fn update(&mut self, _context: (), time: &FrameTime, input: &InputState) {
+        T::update(self, time, input);
+    }
+
+    fn input(&mut self, _context: (), event: &InputEvent) {
+        T::input(self, event);
+    }
+
+    fn tile(&mut self, _context: (), tile: &Tile) {
+        self.mvp.as_mut().tile = tile.matrix();
     }
 }
 
@@ -61,16 +85,33 @@ impl<T> MvpController<T> {
     pub fn set_view(&mut self, view: Matrix4<f32>) {
         self.mvp.as_mut().view = view;
     }
+
+    pub fn set_fovy(&mut self, fovy: Rad<f32>) {
+        self.set_projection(self.projection.with_fovy(fovy));
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        if projection != self.projection {
+            self.projection = projection;
+            self.mvp.as_mut().projection = self.projection.matrix(self.aspect);
+        }
+    }
 }
 
-impl<T: 'static> MvpController<T> where MvpController<T>: Content<()> {
+impl<T: MvpState + 'static> MvpController<T> {
     pub fn from_model_view(model: Matrix4<f32>, view: Matrix4<f32>, fovy: Rad<f32>, state: T)
         -> Configurator<PipelineConfiguration>
+    {
+        Self::from_projection(model, view, Projection::from_fovy(fovy), state)
+    }
+
+    pub fn from_projection(model: Matrix4<f32>, view: Matrix4<f32>, projection: Projection, state: T)
+        -> Configurator<PipelineConfiguration>
     {
         func_box!(move |mut pipeline: PipelineConfiguration| {
-            let mvp_s = Mvp { model, view, projection: create_projection(1.0, fovy) };
+            let mvp_s = Mvp { model, view, projection: projection.matrix(1.0), tile: Matrix4::identity() };
             let mvp: Uniform<Mvp> = pipeline.uniforms().add("Mvp", mvp_s, wgpu::ShaderStages::VERTEX).value();
-            pipeline.add_listener(typed_box!(dyn Content<()>, MvpController { mvp, fovy, state }));
+            pipeline.add_listener(typed_box!(dyn Content<()>, MvpController { mvp, projection, aspect: 1.0, state }));
             pipeline
         })
     }
@@ -112,9 +153,102 @@ impl AnimationState {
     }
 }
 
-impl Content<()> for MvpController<AnimationState> {
-    fn update(&mut self, _context: (), dt: Duration) {
-        let (sin, cos) = (self.state.animation_speed * dt.as_secs_f32()).sin_cos();
-        self.set_model(create_rotation([Rad(sin), Rad(cos), Rad(0.0)]));
+impl MvpState for AnimationState {
+    fn update(controller: &mut MvpController<Self>, time: &FrameTime, _input: &InputState) {
+        let (sin, cos) = (controller.state.animation_speed * time.total_secs()).sin_cos();
+        controller.set_model(create_rotation([Rad(sin), Rad(cos), Rad(0.0)]));
+    }
+}
+
+
+// OrbitCamera
+
+#[allow(dead_code)]
+impl MvpController<OrbitCamera> {
+    pub fn orbit<P: Into<Point3<f32>>, F: Into<Rad<f32>>>(
+        model: Matrix4<f32>,
+        eye: P,
+        look_at: P,
+        up: Vector3<f32>,
+        fovy: F,
+    ) -> Configurator<PipelineConfiguration> {
+        let fovy = fovy.into();
+        let orbit = OrbitCamera::new(eye.into(), look_at.into(), up).with_fovy(fovy);
+        Self::from_model_view(model, orbit.view(), fovy, orbit)
+    }
+
+    pub fn orbit_example() -> Configurator<PipelineConfiguration> {
+        Self::orbit(
+            Matrix4::identity(),
+            point3(5.0, 1.5, 3.0),
+            point3(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+            Deg(45.0),
+        )
+    }
+}
+
+impl MvpState for OrbitCamera {
+    fn resize(&mut self, width: u32, height: u32) {
+        OrbitCamera::resize(self, width, height);
+    }
+
+    fn update(controller: &mut MvpController<Self>, time: &FrameTime, _input: &InputState) {
+        if controller.state.update(time) {
+            controller.set_view(controller.state.view());
+        }
+    }
+
+    fn input(controller: &mut MvpController<Self>, event: &InputEvent) {
+        if controller.state.input(event) {
+            controller.set_view(controller.state.view());
+            controller.set_projection(controller.state.projection());
+        }
+    }
+}
+
+
+// FlyCamera
+
+#[allow(dead_code)]
+impl MvpController<FlyCamera> {
+    pub fn fly<F: Into<Rad<f32>>>(model: Matrix4<f32>, camera: FlyCamera, fovy: F) -> Configurator<PipelineConfiguration> {
+        Self::from_model_view(model, camera.view(), fovy.into(), camera)
+    }
+}
+
+impl MvpState for FlyCamera {
+    fn update(controller: &mut MvpController<Self>, time: &FrameTime, input: &InputState) {
+        if controller.state.update(time, input) {
+            controller.set_view(controller.state.view());
+        }
+    }
+
+    fn input(controller: &mut MvpController<Self>, event: &InputEvent) {
+        if controller.state.input(event) {
+            controller.set_view(controller.state.view());
+        }
+    }
+}
+
+
+// CameraTimeline
+
+#[allow(dead_code)]
+impl MvpController<CameraTimeline> {
+    // Starts at the first key of the timeline path, or at the given view when there is none
+    pub fn timeline<F: Into<Rad<f32>>>(model: Matrix4<f32>, view: Matrix4<f32>, fovy: F, timeline: CameraTimeline)
+        -> Configurator<PipelineConfiguration>
+    {
+        Self::from_model_view(model, view, fovy.into(), timeline)
+    }
+}
+
+impl MvpState for CameraTimeline {
+    fn update(controller: &mut MvpController<Self>, time: &FrameTime, _input: &InputState) {
+        if let Some(key) = controller.state.update(time) {
+            controller.set_view(key.view());
+            controller.set_fovy(key.fovy);
+        }
     }
 }
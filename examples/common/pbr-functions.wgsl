@@ -0,0 +1,64 @@
+// Texture map factors, fragment shaders sampling maps set them before shading. Metallic and
+// roughness are read from the blue and green channels as in glTF.
+var<private> base_color_map: vec4<f32> = vec4<f32>(1.0, 1.0, 1.0, 1.0);
+var<private> metallic_roughness_map: vec4<f32> = vec4<f32>(1.0, 1.0, 1.0, 1.0);
+
+const PBR_PI: f32 = 3.14159265;
+
+fn distribution_ggx(dotNH: f32, roughness: f32) -> f32 {
+    let a2 = roughness * roughness * roughness * roughness;
+    let d = dotNH * dotNH * (a2 - 1.0) + 1.0;
+    return a2 / (PBR_PI * d * d);
+}
+
+fn geometry_smith(dotNV: f32, dotNL: f32, roughness: f32) -> f32 {
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    return dotNV / (dotNV * (1.0 - k) + k) * dotNL / (dotNL * (1.0 - k) + k);
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+fn pbr(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>) -> vec4<f32> {
+    return pbr_both(position, normal, color, 0.0);
+}
+
+// Metallic-roughness counterpart of `color_both`, a non zero `back` lights the back faces too.
+// Light intensities keep the scale of the Blinn-Phong helpers, so the BRDF is multiplied by pi.
+fn pbr_both(position: vec4<f32>, normal: vec4<f32>, color: vec3<f32>, back: f32) -> vec4<f32> {
+    let base = material_u.base_color.rgb * base_color_map.rgb * color;
+    let metallic = clamp(material_u.metallic * metallic_roughness_map.b, 0.0, 1.0);
+    let roughness = clamp(material_u.roughness * metallic_roughness_map.g, 0.04, 1.0);
+
+    let V: vec3<f32> = normalize(camera_u.eye.xyz - position.xyz);
+    var N: vec3<f32> = normalize(normal.xyz);
+    if (back != 0.0 && dot(N, V) < 0.0) {
+        N = -N;
+    }
+    let dotNV = max(dot(N, V), 1e-4);
+    let f0 = mix(vec3<f32>(0.04), base, metallic);
+
+    var radiance = vec3<f32>(0.0);
+    for (var i = 0u; i < light_u.count; i++) {
+        let light = light_u.lights[i];
+        let incidence = incidence(light, position.xyz);
+        let L: vec3<f32> = incidence.xyz;
+        let H: vec3<f32> = normalize(L + V);
+        let dotNL = dot(N, L);
+        if (dotNL <= 0.0) {
+            continue;
+        }
+
+        let F = fresnel_schlick(max(dot(H, V), 0.0), f0);
+        let specular = distribution_ggx(max(dot(N, H), 0.0), roughness) * geometry_smith(dotNV, dotNL, roughness)
+            * F / (4.0 * dotNV * dotNL + 1e-4);
+        let diffuse = (1.0 - F) * (1.0 - metallic) * base / PBR_PI;
+        let brdf = light.diffuse_intensity * light.color.rgb * diffuse
+            + light.specular_intensity * light.specular_color.rgb * specular;
+        radiance += PBR_PI * brdf * dotNL * incidence.w;
+    }
+
+    let ambient = light_u.ambient_intensity * ambient_occlusion * base;
+    return vec4(ambient + radiance + material_u.emissive.rgb, 1.0);
+}
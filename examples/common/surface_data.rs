@@ -180,7 +180,7 @@ impl Surface<'static> {
         Self::new("torus", || Self::parametric(&torus, (-PI, PI, 40), (-PI, PI, 15), (2.0, 0.4, 2.0))),
     ];
 
-    const fn new(name: &str, data: fn() -> SurfaceData) -> Surface {
+    const fn new(name: &str, data: fn() -> SurfaceData) -> Surface<'_> {
         Surface { name, data }
     }
 
@@ -288,7 +288,6 @@ impl<V, const L: usize> IntoIterator for Mesh<V, L>{
 }
 
 impl<V, const L: usize, U> From<Mesh<V, L>> for Vec<U> where V: Into<U> {
-    #[must_use]
     fn from(mesh: Mesh<V, L>) -> Self {
         mesh.mesh.into_iter().flatten().map(V::into).collect()
     }
@@ -0,0 +1,148 @@
+use anyhow::{bail, Result};
+
+use crate::bindings::{sampled_bindings, Binding};
+use crate::webgpu::WebGPUDevice;
+
+//
+// AttachmentConfiguration
+
+#[must_use]
+pub struct AttachmentConfiguration {
+    name: String,
+    format: Option<wgpu::TextureFormat>,
+    scale: f32,
+    filter: wgpu::FilterMode,
+}
+
+impl AttachmentConfiguration {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            format: None,
+            scale: 1.0,
+            filter: wgpu::FilterMode::Linear,
+        }
+    }
+
+    pub fn with_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: wgpu::FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+//
+// Attachment
+
+pub(crate) struct Attachment {
+    pub(crate) name: String,
+    pub(crate) format: wgpu::TextureFormat,
+    scale: f32,
+    sampler: wgpu::Sampler,
+    texture: Option<(wgpu::Texture, wgpu::TextureView)>,
+}
+
+impl Attachment {
+    fn new(wg: &WebGPUDevice, conf: AttachmentConfiguration, surface_format: wgpu::TextureFormat) -> Self {
+        let sampler = wg.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: conf.filter,
+            min_filter: conf.filter,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        Self {
+            name: conf.name,
+            format: conf.format.unwrap_or(surface_format),
+            scale: conf.scale,
+            sampler,
+            texture: None,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    fn size(&self, width: u32, height: u32) -> (u32, u32) {
+        let scaled = |size: u32| ((size as f32 * self.scale).round() as u32).max(1);
+        (scaled(width), scaled(height))
+    }
+
+    fn allocate(&mut self, wg: &WebGPUDevice, width: u32, height: u32) {
+        let (width, height) = self.size(width, height);
+        let usage = wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC;
+        let label = format!("Attachment {}", self.name);
+        let texture = wg.create_texture(&label, width, height, usage, self.format);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.texture = Some((texture, view));
+    }
+
+    pub(crate) fn target(&self) -> (&wgpu::TextureView, u32, u32) {
+        let (texture, view) = self.texture.as_ref().expect("Attachment allocated");
+        (view, texture.width(), texture.height())
+    }
+
+    pub(crate) fn bindings(&self) -> [Binding<'_>; 2] {
+        let views = self.texture.iter().map(|(_, view)| view).collect();
+        sampled_bindings(views, &self.sampler, self.format)
+    }
+}
+
+//
+// Attachments
+
+#[derive(Default)]
+pub(crate) struct Attachments {
+    attachments: Vec<Attachment>,
+    width: u32,
+    height: u32,
+}
+
+impl Attachments {
+    pub(crate) fn new(
+        wg: &WebGPUDevice,
+        confs: Vec<AttachmentConfiguration>,
+        surface_format: wgpu::TextureFormat,
+    ) -> Result<Self> {
+        let mut attachments: Vec<Attachment> = vec![];
+        for conf in confs {
+            if attachments.iter().any(|attachment| attachment.name == conf.name) {
+                bail!("Duplicate attachment '{}'", conf.name);
+            }
+            attachments.push(Attachment::new(wg, conf, surface_format));
+        }
+        Ok(Self { attachments, width: 0, height: 0 })
+    }
+
+    pub(crate) fn index(&self, name: &str) -> Option<usize> {
+        self.attachments.iter().position(|attachment| attachment.name == name)
+    }
+
+    #[allow(clippy::indexing_slicing)]
+    pub(crate) fn get(&self, index: usize) -> &Attachment {
+        &self.attachments[index]
+    }
+
+    // Reallocates transient textures for the new target size, returns whether they changed.
+    pub(crate) fn resize(&mut self, wg: &WebGPUDevice, width: u32, height: u32) -> bool {
+        if self.attachments.is_empty() || (self.width == width && self.height == height) {
+            return false;
+        }
+        self.width = width;
+        self.height = height;
+        for attachment in &mut self.attachments {
+            attachment.allocate(wg, width, height);
+        }
+        true
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use image::{io::Reader as ImageReader, RgbaImage};
 
+use crate::feedback::Feedbacks;
+use crate::graph::{Input, Resource, Resources};
 use crate::usize_as_u32;
 use crate::webgpu::WebGPUDevice;
 
@@ -30,6 +32,12 @@ impl BindGroupVariants {
         bindings: Vec<Binding>,
         variants: Vec<Vec<usize>>
     ) -> Self {
+        let layout = Self::layout(wg, label, &bindings);
+        let groups = Self::groups(wg, label, &layout, &bindings, variants);
+        Self { layout, groups }
+    }
+
+    pub(crate) fn layout(wg: &WebGPUDevice, label: &str, bindings: &[Binding]) -> wgpu::BindGroupLayout {
         let layouts = &bindings.iter().enumerate()
             .map(|(index, binding)| wgpu::BindGroupLayoutEntry {
                 binding: usize_as_u32(index),
@@ -39,16 +47,24 @@ impl BindGroupVariants {
             })
             .collect::<Vec<_>>();
 
-        let layout = wg.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        wg.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some(format!("{label} Bing Group Layout").as_str()),
             entries: layouts,
-        });
+        })
+    }
 
-        #[allow(clippy::indexing_slicing)]
-        let groups: Vec<wgpu::BindGroup> = variants.into_iter().enumerate()
+    #[allow(clippy::needless_pass_by_value, clippy::indexing_slicing)]
+    pub(crate) fn groups(
+        wg: &WebGPUDevice,
+        label: &str,
+        layout: &wgpu::BindGroupLayout,
+        bindings: &[Binding],
+        variants: Vec<Vec<usize>>
+    ) -> Vec<wgpu::BindGroup> {
+        variants.into_iter().enumerate()
             .map(|(no, variant)| wg.device.create_bind_group(&wgpu::BindGroupDescriptor {
                 label: Some(format!("{label} Bing Group, variant {no}").as_str()),
-                layout: &layout,
+                layout,
                 entries: &bindings.iter()
                     .enumerate()
                     .map(|(index, binding)| wgpu::BindGroupEntry {
@@ -56,9 +72,7 @@ impl BindGroupVariants {
                         resource: binding.resources[*variant.get(index).unwrap_or(&0)].clone()
                     })
                     .collect::<Vec<_>>(),
-            })).collect();
-
-        Self { layout, groups }
+            })).collect()
     }
 }
 
@@ -143,11 +157,11 @@ pub struct Texture {
 }
 
 impl Texture {
-    fn bindings(&self) -> [Binding; 2] {
+    fn bindings(&self) -> [Binding<'_>; 2] {
         [
             Binding {
                 resources: vec![wgpu::BindingResource::TextureView(&self.view)],
-                visibility: wgpu::ShaderStages::FRAGMENT,
+                visibility: wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
                 ty: wgpu::BindingType::Texture {
                     multisampled: false,
                     view_dimension: wgpu::TextureViewDimension::D2,
@@ -156,32 +170,140 @@ impl Texture {
             },
             Binding {
                 resources: vec![wgpu::BindingResource::Sampler(&self.sampler)],
-                visibility: wgpu::ShaderStages::FRAGMENT,
+                visibility: wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
                 ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
             },
         ]
     }
 }
 
+//
+// Sampled textures
+
+pub(crate) fn sampled_bindings<'a>(
+    views: Vec<&'a wgpu::TextureView>,
+    sampler: &'a wgpu::Sampler,
+    format: wgpu::TextureFormat,
+) -> [Binding<'a>; 2] {
+    let sample_type = format.sample_type(None).expect("Color format");
+    let filterable = matches!(sample_type, wgpu::TextureSampleType::Float { filterable: true });
+    [
+        Binding {
+            resources: views.into_iter().map(wgpu::BindingResource::TextureView).collect(),
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                sample_type,
+            },
+        },
+        Binding {
+            resources: vec![wgpu::BindingResource::Sampler(sampler)],
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Sampler(if filterable {
+                wgpu::SamplerBindingType::Filtering
+            } else {
+                wgpu::SamplerBindingType::NonFiltering
+            }),
+        },
+    ]
+}
+
+pub(crate) fn sampled_declaration(name: &str, format: wgpu::TextureFormat, group: u32, binding: usize) -> String {
+    let texel = match format.sample_type(None) {
+        Some(wgpu::TextureSampleType::Sint) => "i32",
+        Some(wgpu::TextureSampleType::Uint) => "u32",
+        _ => "f32",
+    };
+    format!(
+        "@group({group}) @binding({binding}) var {name}_texture: texture_2d<{texel}>;\n\
+         @group({group}) @binding({}) var {name}_sampler: sampler;\n",
+        binding + 1,
+    )
+}
+
+//
+// Storage textures
+
+pub(crate) fn storage_binding<'a>(views: Vec<&'a wgpu::TextureView>, format: wgpu::TextureFormat) -> Binding<'a> {
+    Binding {
+        resources: views.into_iter().map(wgpu::BindingResource::TextureView).collect(),
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        },
+    }
+}
+
+// WGSL names the storage formats as wgpu does, in lower case
+pub(crate) fn storage_declaration(name: &str, format: wgpu::TextureFormat, group: u32, binding: usize) -> String {
+    let format = format!("{format:?}").to_lowercase();
+    format!("@group({group}) @binding({binding}) var {name}_storage: texture_storage_2d<{format}, write>;\n")
+}
+
 //
 // Textures
 
 pub(crate) struct Textures {
-    _textures: Vec<Texture>,
-    pub(crate) variants: BindGroupVariants,
+    textures: Vec<Texture>,
+    inputs: Vec<Input>,
+    pub(crate) layout: wgpu::BindGroupLayout,
+    pub(crate) groups: Vec<wgpu::BindGroup>,
+    pub(crate) declarations: String,
 }
 
 impl Textures {
-    pub(crate) fn new(wg: &WebGPUDevice, texture_infos: &[TextureInfo]) -> Result<Self> {
+    pub(crate) fn new(
+        wg: &WebGPUDevice,
+        texture_infos: &[TextureInfo],
+        inputs: Vec<Input>,
+        resources: &Resources,
+        group: u32,
+    ) -> Result<Self> {
         let textures: Vec<Texture> = texture_infos.iter()
             .map(|info| info.create_texture(wg))
             .collect::<Result<Vec<_>>>()?;
 
-        let bindings = textures.iter().flat_map(Texture::bindings).collect::<Vec<_>>();
+        let mut declarations = String::new();
+        let mut binding = textures.len() * 2;
+        for input in &inputs {
+            declarations.push_str(&resources.declaration(input.resource, group, binding));
+            binding += 2;
+        }
+
+        let (bindings, _) = Self::bindings(&textures, &inputs, resources);
+        let layout = BindGroupVariants::layout(wg, "Textures", &bindings);
 
-        Ok(Self {
-            variants: BindGroupVariants::new(wg, "Textures", bindings, vec![vec![0, 0]]),
-            _textures: textures,
-        })
+        let mut textures = Self { textures, inputs, layout, groups: vec![], declarations };
+        if !textures.is_transient() {
+            textures.bind(wg, resources);
+        }
+        Ok(textures)
+    }
+
+    pub(crate) fn is_transient(&self) -> bool {
+        self.inputs.iter().any(|input| matches!(input.resource, Resource::Attachment(_)))
+    }
+
+    pub(crate) fn bind(&mut self, wg: &WebGPUDevice, resources: &Resources) {
+        let (bindings, variants) = Self::bindings(&self.textures, &self.inputs, resources);
+        self.groups = BindGroupVariants::groups(wg, "Textures", &self.layout, &bindings, variants);
+    }
+
+    fn bindings<'a>(textures: &'a [Texture], inputs: &[Input], resources: &'a Resources)
+        -> (Vec<Binding<'a>>, Vec<Vec<usize>>)
+    {
+        let mut bindings = textures.iter().flat_map(Texture::bindings).collect::<Vec<_>>();
+        let phases = if inputs.is_empty() { 1 } else { Feedbacks::PHASES };
+        let mut variants = vec![vec![0; bindings.len()]; phases];
+        for input in inputs {
+            bindings.extend(resources.bindings(input.resource));
+            for (phase, variant) in variants.iter_mut().enumerate() {
+                variant.extend([resources.view_index(input, phase), 0]);
+            }
+        }
+        (bindings, variants)
     }
 }
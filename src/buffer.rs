@@ -17,6 +17,24 @@ pub(crate) struct BufferLayout {
     item_alignment: usize,
 }
 
+impl BufferLayout {
+    // Distance between items, uniforms bound per item start at the offset alignment
+    fn stride(&self) -> usize {
+        self.item_size.div_ceil(self.item_alignment) * self.item_alignment
+    }
+
+    fn contents<T: Pod>(&self, items: &[T]) -> Vec<u8> {
+        let stride = self.stride();
+        if stride == self.item_size {
+            return cast_slice(items).to_vec();
+        }
+        let mut contents = vec![0; stride * items.len().saturating_sub(1) + self.item_size];
+        for (chunk, item) in contents.chunks_mut(stride).zip(items) {
+            chunk[..self.item_size].copy_from_slice(bytemuck::bytes_of(item));
+        }
+        contents
+    }
+}
 
 // SmartBuffer
 
@@ -35,11 +53,11 @@ impl<F> SmartBuffer<F> {
         }
     }
 
-    pub(crate) fn resources(&self) -> Vec<BindingResource> {
+    pub(crate) fn resources(&self) -> Vec<BindingResource<'_>> {
         (0..self.layout.item_count)
             .map(|index| BindingResource::Buffer(BufferBinding {
                 buffer: &self.buffer,
-                offset: (index * self.layout.item_alignment) as BufferAddress,
+                offset: (index * self.layout.stride()) as BufferAddress,
                 size: BufferSize::new(self.layout.item_size as u64),
             }))
             .collect()
@@ -64,7 +82,7 @@ impl BufferWriter {
     pub fn write_slice<B: Pod + 'static>(&self, slice: &[B]) {
         self.check_type::<B>();
         assert_eq!(self.layout.item_count, slice.len(), "Invalid slice length");
-        self.queue.write_buffer(&self.buffer, 0, cast_slice(slice));
+        self.queue.write_buffer(&self.buffer, 0, &self.layout.contents(slice));
     }
 }
 
@@ -81,16 +99,17 @@ pub struct SmartBufferDescriptor<F> {
 
 impl<'a, F> SmartBufferDescriptor<F> {
     pub fn new<T: Pod>(label: String, items: &'a [T], usage: BufferUsages, format: F, alignment: usize) -> Self {
+        let layout = BufferLayout {
+            item_count: items.len(),
+            type_id: TypeId::of::<T>(),
+            item_size: size_of::<T>(),
+            item_alignment: alignment
+        };
         Self {
             label,
-            contents: cast_slice(items).to_vec(),
+            contents: layout.contents(items),
             usage,
-            layout: BufferLayout {
-                item_count: items.len(),
-                type_id: TypeId::of::<T>(),
-                item_size: size_of::<T>(),
-                item_alignment: alignment
-            },
+            layout,
             format,
         }
     }
@@ -140,6 +159,7 @@ impl IndexBufferInfo for u16 {
 impl<T: Pod> BufferInfo<ShaderStages> for T {
     const USAGE: BufferUsages = BufferUsages::UNIFORM.union(BufferUsages::COPY_DST);
     const FORMAT: ShaderStages = ShaderStages::VERTEX;
+    const ALIGNMENT: usize = 256;
 }
 
 
@@ -0,0 +1,453 @@
+use core::f32::consts::PI;
+use core::time::Duration;
+use std::time::Instant;
+
+use cgmath::{Angle, Basis3, Deg, EuclideanSpace, InnerSpace, Matrix4, Point3, Rad, Rotation, Rotation3, Vector3, Zero};
+use winit::event::{ElementState, MouseButton, VirtualKeyCode};
+
+use crate::{FrameTime, InputEvent, InputState};
+use crate::transforms::{create_view, Frustum, Projection};
+
+//
+// OrbitCamera
+
+// Turntable camera circling a target: left-drag rotates, scroll or pinch zooms,
+// right- or middle-drag pans, double-click refocuses under the cursor, a key resets.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct OrbitCamera {
+    target: Point3<f32>,
+    // Unit vector from the target to the eye
+    direction: Vector3<f32>,
+    distance: f32,
+    up: Vector3<f32>,
+    home: (Point3<f32>, Vector3<f32>, f32, Vector3<f32>, Projection),
+    projection: Projection,
+    size: (f32, f32),
+    rotate_speed: Rad<f32>,
+    zoom_speed: f32,
+    distance_range: (f32, f32),
+    inertia: f32,
+    reset_key: VirtualKeyCode,
+    drag: Option<MouseButton>,
+    dragged: (f32, f32),
+    velocity: (f32, f32),
+    cursor: [f32; 2],
+    last_click: Option<(Instant, [f32; 2])>,
+}
+
+impl OrbitCamera {
+    const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+    const CLICK_SLOP: f32 = 0.01;
+    // Keeps the eye off the up axis, where the view would flip
+    const PITCH_LIMIT: f32 = 0.01;
+
+    pub fn new(eye: Point3<f32>, target: Point3<f32>, up: Vector3<f32>) -> Self {
+        let (direction, distance, up) = Self::placement(eye, target, up);
+        let projection = Projection::perspective(Rad(PI / 4.0));
+        Self {
+            target,
+            direction,
+            distance,
+            up,
+            home: (target, direction, distance, up, projection),
+            projection,
+            size: (1.0, 1.0),
+            rotate_speed: Rad(0.005),
+            zoom_speed: 1.1,
+            distance_range: (distance / 100.0, distance * 100.0),
+            inertia: 0.2,
+            reset_key: VirtualKeyCode::R,
+            drag: None,
+            dragged: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            cursor: [0.5, 0.5],
+            last_click: None,
+        }
+    }
+
+    // Field of view of the projection the camera is used with, pan and refocus follow the cursor with it
+    pub fn with_fovy<F: Into<Rad<f32>>>(self, fovy: F) -> Self {
+        self.with_projection(Projection::from_fovy(fovy.into()))
+    }
+
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self.home.4 = projection;
+        self
+    }
+
+    // Rotation per pixel of mouse motion
+    pub fn with_rotate_speed<F: Into<Rad<f32>>>(mut self, speed: F) -> Self {
+        self.rotate_speed = speed.into();
+        self
+    }
+
+    // Distance factor per scroll line
+    pub fn with_zoom_speed(mut self, zoom_speed: f32) -> Self {
+        self.zoom_speed = zoom_speed.max(1.0);
+        self
+    }
+
+    pub fn with_distance_range(mut self, min: f32, max: f32) -> Self {
+        self.distance_range = (min, max);
+        self.distance = self.distance.clamp(min, max);
+        self
+    }
+
+    // Seconds the rotation keeps spinning down after a drag is released, 0 stops at once
+    pub fn with_inertia(mut self, inertia: f32) -> Self {
+        self.inertia = inertia.max(0.0);
+        self
+    }
+
+    pub fn with_reset_key(mut self, key: VirtualKeyCode) -> Self {
+        self.reset_key = key;
+        self
+    }
+
+    #[must_use]
+    pub fn eye(&self) -> Point3<f32> {
+        self.target + self.direction * self.distance
+    }
+
+    #[must_use]
+    pub fn target(&self) -> Point3<f32> {
+        self.target
+    }
+
+    #[must_use]
+    pub fn up(&self) -> Vector3<f32> {
+        self.up
+    }
+
+    #[must_use]
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    // Zooming scales the height of an orthographic projection
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Matrix4<f32> {
+        create_view(self.eye(), self.target, self.up)
+    }
+
+    // Places the camera keeping the settings, the reset key still returns to the initial placement
+    pub fn look(&mut self, eye: Point3<f32>, target: Point3<f32>, up: Vector3<f32>) {
+        self.target = target;
+        (self.direction, self.distance, self.up) = Self::placement(eye, target, up);
+        self.velocity = (0.0, 0.0);
+    }
+
+    // Direction, distance and up of a placement. An eye on the target looks along a perpendicular
+    // of up from a unit distance, an eye on the up axis takes a perpendicular of the direction as up.
+    fn placement(eye: Point3<f32>, target: Point3<f32>, up: Vector3<f32>) -> (Vector3<f32>, f32, Vector3<f32>) {
+        let up = if up.magnitude2() > f32::EPSILON { up.normalize() } else { Vector3::unit_y() };
+        let offset = eye - target;
+        let distance = offset.magnitude();
+        let (direction, distance) =
+            if distance > f32::EPSILON { (offset / distance, distance) } else { (Self::perpendicular(up), 1.0) };
+        let up = if direction.cross(up).magnitude2() > f32::EPSILON { up } else { Self::perpendicular(direction) };
+        (direction, distance, up)
+    }
+
+    fn perpendicular(v: Vector3<f32>) -> Vector3<f32> {
+        let axis = if v.x.abs() < 0.9 { Vector3::unit_x() } else { Vector3::unit_y() };
+        v.cross(axis).normalize()
+    }
+
+    pub fn reset(&mut self) {
+        (self.target, self.direction, self.distance, self.up, self.projection) = self.home;
+        self.velocity = (0.0, 0.0);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.size = (width.max(1) as f32, height.max(1) as f32);
+    }
+
+    // Returns whether the view changed
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn input(&mut self, event: &InputEvent) -> bool {
+        match *event {
+            InputEvent::Key { key, state: ElementState::Pressed, .. } if key == self.reset_key => {
+                self.reset();
+                true
+            }
+            InputEvent::MouseButton { button, state: ElementState::Pressed, .. } => {
+                self.drag = Some(button);
+                self.velocity = (0.0, 0.0);
+                button == MouseButton::Left && self.click()
+            }
+            InputEvent::MouseButton { button, state: ElementState::Released, .. } if self.drag == Some(button) => {
+                self.drag = None;
+                false
+            }
+            InputEvent::CursorMoved { normalized, .. } => {
+                self.cursor = normalized;
+                false
+            }
+            InputEvent::MouseMotion { delta } => {
+                let (dx, dy) = (delta.0 as f32, delta.1 as f32);
+                match self.drag {
+                    Some(MouseButton::Left) => {
+                        self.dragged = (self.dragged.0 + dx, self.dragged.1 + dy);
+                        self.rotate(dx, dy);
+                        true
+                    }
+                    Some(MouseButton::Right | MouseButton::Middle) => {
+                        self.pan(dx, dy);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            InputEvent::Scroll { delta } => {
+                self.zoom(self.zoom_speed.powf(-delta[1]));
+                true
+            }
+            InputEvent::Magnify { delta } => {
+                self.zoom(1.0 / (1.0 + delta).max(0.1));
+                true
+            }
+            InputEvent::Focused(false) => {
+                self.drag = None;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    // Carries on the rotation of a released drag in wall-clock time, returns whether the view changed
+    pub fn update(&mut self, time: &FrameTime) -> bool {
+        let dt = time.real_delta_secs();
+        if dt <= 0.0 {
+            return false;
+        }
+        if self.drag == Some(MouseButton::Left) {
+            self.velocity = (self.dragged.0 / dt, self.dragged.1 / dt);
+            self.dragged = (0.0, 0.0);
+            return false;
+        }
+        if self.inertia <= 0.0 || self.velocity == (0.0, 0.0) {
+            return false;
+        }
+        self.rotate(self.velocity.0 * dt, self.velocity.1 * dt);
+        let decay = (-dt / self.inertia).exp();
+        self.velocity = (self.velocity.0 * decay, self.velocity.1 * decay);
+        if self.velocity.0.hypot(self.velocity.1) < 1.0 {
+            self.velocity = (0.0, 0.0);
+        }
+        true
+    }
+
+    fn rotate(&mut self, dx: f32, dy: f32) {
+        let yaw = Basis3::from_axis_angle(self.up, -self.rotate_speed * dx);
+        let direction = yaw.rotate_vector(self.direction);
+
+        // Angle from the up axis, dragging down raises the eye
+        let polar = direction.dot(self.up).clamp(-1.0, 1.0).acos();
+        let pitched = (polar - self.rotate_speed.0 * dy).clamp(Self::PITCH_LIMIT, PI - Self::PITCH_LIMIT);
+        let right = self.up.cross(direction).normalize();
+        let pitch = Basis3::from_axis_angle(right, Rad(pitched - polar));
+        self.direction = pitch.rotate_vector(direction).normalize();
+    }
+
+    fn pan(&mut self, dx: f32, dy: f32) {
+        let (right, up) = self.screen_axes();
+        let scale = 2.0 * self.half_height() / self.size.1;
+        self.target += (up * dy - right * dx) * scale;
+    }
+
+    // An orthographic view keeps the eye within its depth range and scales its height instead,
+    // limited as if the eye had moved within the distance range
+    fn zoom(&mut self, factor: f32) {
+        let (min, max) = self.distance_range;
+        match (self.projection.frustum(), self.home.4.frustum()) {
+            (Frustum::Orthographic { half_height }, Frustum::Orthographic { half_height: home }) => {
+                let distance = self.distance * half_height / home;
+                let zoomed = (distance * factor).clamp(min, max);
+                self.projection = self.projection.with_half_height(half_height * zoomed / distance);
+            }
+            _ => self.distance = (self.distance * factor).clamp(min, max),
+        }
+    }
+
+    // Moves the target to the point under the cursor on the plane through the target
+    fn click(&mut self) -> bool {
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(time, [x, y])| {
+            now - time < Self::DOUBLE_CLICK
+                && (x - self.cursor[0]).abs() < Self::CLICK_SLOP
+                && (y - self.cursor[1]).abs() < Self::CLICK_SLOP
+        });
+        if double {
+            self.last_click = None;
+            let (right, up) = self.screen_axes();
+            let half_height = self.half_height();
+            let half_width = half_height * self.size.0 / self.size.1;
+            self.target += right * (2.0 * self.cursor[0] - 1.0) * half_width
+                + up * (1.0 - 2.0 * self.cursor[1]) * half_height;
+        } else {
+            self.last_click = Some((now, self.cursor));
+        }
+        double
+    }
+
+    fn screen_axes(&self) -> (Vector3<f32>, Vector3<f32>) {
+        let right = self.up.cross(self.direction).normalize();
+        (right, self.direction.cross(right))
+    }
+
+    // Half of the visible height at the target
+    fn half_height(&self) -> f32 {
+        self.projection.half_height(self.distance)
+    }
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self::new(Point3::new(0.0, 0.0, 5.0), Point3::origin(), Vector3::unit_y())
+    }
+}
+
+//
+// FlyCamera
+
+// First-person camera: WASD moves, Q and E go down and up, shift speeds up, the mouse looks around.
+// Yaw is measured from +x towards +z and pitch from the horizontal plane, y is up.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct FlyCamera {
+    position: Point3<f32>,
+    yaw: Rad<f32>,
+    pitch: Rad<f32>,
+    speed: f32,
+    boost: f32,
+    sensitivity: Rad<f32>,
+    pitch_limit: Rad<f32>,
+    look_button: Option<MouseButton>,
+    looking: bool,
+}
+
+impl FlyCamera {
+    pub fn new<P: Into<Point3<f32>>, Y: Into<Rad<f32>>, T: Into<Rad<f32>>>(position: P, yaw: Y, pitch: T) -> Self {
+        let pitch_limit = Deg(89.0).into();
+        Self {
+            position: position.into(),
+            yaw: yaw.into(),
+            pitch: Self::clamp(pitch.into(), pitch_limit),
+            speed: 2.0,
+            boost: 4.0,
+            sensitivity: Rad(0.003),
+            pitch_limit,
+            look_button: Some(MouseButton::Left),
+            looking: false,
+        }
+    }
+
+    // Units per second
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    // Speed factor while shift is held
+    pub fn with_boost(mut self, boost: f32) -> Self {
+        self.boost = boost;
+        self
+    }
+
+    // Rotation per pixel of mouse motion
+    pub fn with_sensitivity<F: Into<Rad<f32>>>(mut self, sensitivity: F) -> Self {
+        self.sensitivity = sensitivity.into();
+        self
+    }
+
+    // Largest angle above or below the horizon
+    pub fn with_pitch_limit<F: Into<Rad<f32>>>(mut self, limit: F) -> Self {
+        self.pitch_limit = limit.into();
+        self.pitch = Self::clamp(self.pitch, self.pitch_limit);
+        self
+    }
+
+    // Button held to look around, `None` looks with any mouse motion
+    pub fn with_look_button(mut self, button: Option<MouseButton>) -> Self {
+        self.look_button = button;
+        self
+    }
+
+    #[must_use]
+    pub fn position(&self) -> Point3<f32> {
+        self.position
+    }
+
+    #[must_use]
+    pub fn yaw(&self) -> Rad<f32> {
+        self.yaw
+    }
+
+    #[must_use]
+    pub fn pitch(&self) -> Rad<f32> {
+        self.pitch
+    }
+
+    #[must_use]
+    pub fn forward(&self) -> Vector3<f32> {
+        let (pitch_sin, pitch_cos) = self.pitch.sin_cos();
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        Vector3::new(pitch_cos * yaw_cos, pitch_sin, pitch_cos * yaw_sin).normalize()
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::look_to_rh(self.position, self.forward(), Vector3::unit_y())
+    }
+
+    // Returns whether the view changed
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn input(&mut self, event: &InputEvent) -> bool {
+        match *event {
+            InputEvent::MouseButton { button, state, .. } if Some(button) == self.look_button => {
+                self.looking = state == ElementState::Pressed;
+                false
+            }
+            InputEvent::MouseMotion { delta } if self.looking || self.look_button.is_none() => {
+                self.yaw += self.sensitivity * delta.0 as f32;
+                self.pitch = Self::clamp(self.pitch - self.sensitivity * delta.1 as f32, self.pitch_limit);
+                true
+            }
+            InputEvent::Focused(false) => {
+                self.looking = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    // Moves by the keys held during the frame in wall-clock time, returns whether the view changed
+    pub fn update(&mut self, time: &FrameTime, input: &InputState) -> bool {
+        let axis = |positive, negative| {
+            f32::from(u8::from(input.is_key_down(positive))) - f32::from(u8::from(input.is_key_down(negative)))
+        };
+        let forward = self.forward();
+        let right = forward.cross(Vector3::unit_y()).normalize();
+        let direction = forward * axis(VirtualKeyCode::W, VirtualKeyCode::S)
+            + right * axis(VirtualKeyCode::D, VirtualKeyCode::A)
+            + Vector3::unit_y() * axis(VirtualKeyCode::E, VirtualKeyCode::Q);
+        if direction.is_zero() {
+            return false;
+        }
+        let boost = if input.modifiers().shift() { self.boost } else { 1.0 };
+        self.position += direction.normalize() * self.speed * boost * time.real_delta_secs();
+        true
+    }
+
+    fn clamp(pitch: Rad<f32>, limit: Rad<f32>) -> Rad<f32> {
+        Rad(pitch.0.clamp(-limit.0, limit.0))
+    }
+}
@@ -0,0 +1,391 @@
+use core::ops::{Add, Mul};
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, One, Point3, Quaternion, Rad, Rotation, Vector3, VectorSpace};
+use winit::event::{ElementState, VirtualKeyCode};
+
+use crate::{FrameTime, InputEvent};
+use crate::transforms::create_view;
+
+//
+// CameraKey
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct CameraKey {
+    pub eye: Point3<f32>,
+    pub look_at: Point3<f32>,
+    pub up: Vector3<f32>,
+    pub fovy: Rad<f32>,
+}
+
+impl CameraKey {
+    pub fn new<P: Into<Point3<f32>>, F: Into<Rad<f32>>>(eye: P, look_at: P, up: Vector3<f32>, fovy: F) -> Self {
+        Self { eye: eye.into(), look_at: look_at.into(), up, fovy: fovy.into() }
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Matrix4<f32> {
+        create_view(self.eye, self.look_at, self.up)
+    }
+
+    // Catmull-Rom segment from `k1` to `k2` with `k0` and `k3` as neighbours
+    fn spline(k0: &Self, k1: &Self, k2: &Self, k3: &Self, t: f32) -> Self {
+        let point = |p0: Point3<f32>, p1: Point3<f32>, p2: Point3<f32>, p3: Point3<f32>| {
+            Point3::from_vec(catmull_rom(p0.to_vec(), p1.to_vec(), p2.to_vec(), p3.to_vec(), t))
+        };
+        let up = catmull_rom(k0.up, k1.up, k2.up, k3.up, t);
+        let fovy = catmull_rom(k0.fovy.0, k1.fovy.0, k2.fovy.0, k3.fovy.0, t);
+        Self {
+            eye: point(k0.eye, k1.eye, k2.eye, k3.eye),
+            look_at: point(k0.look_at, k1.look_at, k2.look_at, k3.look_at),
+            up: if up.magnitude2() > 0.0 { up.normalize() } else { k2.up },
+            fovy: Rad(fovy),
+        }
+    }
+
+    // Eye turning around the target from `k1` to `k2`, the distance to the target changes linearly
+    fn orbit(k1: &Self, k2: &Self, t: f32) -> Self {
+        let (offset1, offset2) = (k1.eye - k1.look_at, k2.eye - k2.look_at);
+        let (distance1, distance2) = (offset1.magnitude(), offset2.magnitude());
+        let look_at = k1.look_at + (k2.look_at - k1.look_at) * t;
+        // An eye sitting on its target has no direction to turn
+        let eye = if distance1 > f32::EPSILON && distance2 > f32::EPSILON {
+            let arc = Quaternion::from_arc(offset1 / distance1, offset2 / distance2, Some(k1.up));
+            let direction = Quaternion::one().slerp(arc, t).rotate_vector(offset1 / distance1);
+            look_at + direction * (distance1 + (distance2 - distance1) * t)
+        } else {
+            k1.eye + (k2.eye - k1.eye) * t
+        };
+        let up = k1.up.lerp(k2.up, t);
+        Self {
+            eye,
+            look_at,
+            up: if up.magnitude2() > 0.0 { up.normalize() } else { k2.up },
+            fovy: Rad(k1.fovy.0 + (k2.fovy.0 - k1.fovy.0) * t),
+        }
+    }
+}
+
+fn catmull_rom<V: Copy + Add<Output = V> + Mul<f32, Output = V>>(p0: V, p1: V, p2: V, p3: V, t: f32) -> V {
+    let (t2, t3) = (t * t, t * t * t);
+    p0 * (-0.5 * t3 + t2 - 0.5 * t)
+        + p1 * (1.5 * t3 - 2.5 * t2 + 1.0)
+        + p2 * (-1.5 * t3 + 2.0 * t2 + 0.5 * t)
+        + p3 * (0.5 * t3 - 0.5 * t2)
+}
+
+//
+// Easing
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    #[must_use]
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+        }
+    }
+}
+
+//
+// CameraPath
+
+// Keys at times in seconds joined by a spline, easing applies to every segment between two keys.
+// A looping path whose last key repeats the first one is closed smoothly.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct CameraPath {
+    keys: Vec<(f32, CameraKey)>,
+    easing: Easing,
+    looping: bool,
+    orbit: bool,
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        Self { keys: vec![], easing: Easing::Linear, looping: false, orbit: false }
+    }
+
+    // Eases from one key to the other around the targets
+    pub fn transition(from: CameraKey, to: CameraKey, duration: f32) -> Self {
+        Self::new()
+            .with_key(0.0, from)
+            .with_key(duration, to)
+            .with_easing(Easing::EaseInOut)
+            .with_orbit(true)
+    }
+
+    pub fn with_key(mut self, time: f32, key: CameraKey) -> Self {
+        let index = self.keys.partition_point(|&(t, _)| t <= time);
+        self.keys.insert(index, (time, key));
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_loop(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    // Eyes circle the targets between keys instead of following the spline through them
+    pub fn with_orbit(mut self, orbit: bool) -> Self {
+        self.orbit = orbit;
+        self
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> f32 {
+        self.keys.last().map_or(0.0, |&(time, _)| time)
+    }
+
+    #[must_use]
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    #[must_use]
+    #[allow(clippy::indexing_slicing)]
+    pub fn sample(&self, time: f32) -> Option<CameraKey> {
+        let (&(first, _), &(last, _)) = (self.keys.first()?, self.keys.last()?);
+        let time = if self.looping && last > first {
+            first + (time - first).rem_euclid(last - first)
+        } else {
+            time.clamp(first, last)
+        };
+        if self.keys.len() == 1 {
+            return Some(self.keys[0].1);
+        }
+        let next = self.keys.partition_point(|&(t, _)| t <= time).clamp(1, self.keys.len() - 1);
+        let index = next - 1;
+        let (t1, k1) = self.keys[index];
+        let (t2, k2) = self.keys[next];
+        let local = if t2 > t1 { (time - t1) / (t2 - t1) } else { 1.0 };
+        if self.orbit {
+            return Some(CameraKey::orbit(&k1, &k2, self.easing.apply(local)));
+        }
+        let k0 = self.neighbour(index as isize - 1);
+        let k3 = self.neighbour(next as isize + 1);
+        Some(CameraKey::spline(&k0, &k1, &k2, &k3, self.easing.apply(local)))
+    }
+
+    // Ends repeat the outermost key, closed loops wrap around skipping the repeated key
+    #[allow(clippy::indexing_slicing, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn neighbour(&self, index: isize) -> CameraKey {
+        let last = self.keys.len() as isize - 1;
+        let closed = self.looping && last > 1 && self.keys[0].1 == self.keys[last as usize].1;
+        let index = match index {
+            i if i < 0 && closed => last + i,
+            i if i > last && closed => i - last,
+            i => i.clamp(0, last),
+        };
+        self.keys[index as usize].1
+    }
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//
+// CameraTimeline
+
+// Plays camera paths on the frame clock, so pausing or scaling the clock applies to them too.
+// Digit keys move smoothly to the saved viewpoints, zero replays the scripted path.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct CameraTimeline {
+    script: Option<CameraPath>,
+    playing: Option<CameraPath>,
+    start: Option<f32>,
+    viewpoints: Vec<CameraKey>,
+    transition: f32,
+}
+
+impl CameraTimeline {
+    const VIEWPOINT_KEYS: [VirtualKeyCode; 9] = [
+        VirtualKeyCode::Key1, VirtualKeyCode::Key2, VirtualKeyCode::Key3,
+        VirtualKeyCode::Key4, VirtualKeyCode::Key5, VirtualKeyCode::Key6,
+        VirtualKeyCode::Key7, VirtualKeyCode::Key8, VirtualKeyCode::Key9,
+    ];
+
+    pub fn new() -> Self {
+        Self { script: None, playing: None, start: None, viewpoints: vec![], transition: 1.0 }
+    }
+
+    // Starts playing right away
+    pub fn with_path(mut self, path: CameraPath) -> Self {
+        self.play(path.clone());
+        self.script = Some(path);
+        self
+    }
+
+    pub fn with_viewpoints<I: IntoIterator<Item = CameraKey>>(mut self, viewpoints: I) -> Self {
+        self.viewpoints = viewpoints.into_iter().take(Self::VIEWPOINT_KEYS.len()).collect();
+        self
+    }
+
+    // Seconds spent moving to a viewpoint
+    pub fn with_transition(mut self, transition: f32) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    pub fn play(&mut self, path: CameraPath) {
+        self.playing = Some(path);
+        self.start = None;
+    }
+
+    pub fn transition_to(&mut self, from: CameraKey, to: CameraKey) {
+        self.play(CameraPath::transition(from, to, self.transition));
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = None;
+    }
+
+    // Handles the viewpoint and replay keys, moving away from the current camera
+    pub fn input(&mut self, event: &InputEvent, current: CameraKey) {
+        let InputEvent::Key { key, state: ElementState::Pressed, .. } = *event else {
+            return;
+        };
+        if key == VirtualKeyCode::Key0 {
+            if let Some(script) = self.script.clone() {
+                self.play(script);
+            }
+        } else if let Some(&viewpoint) = Self::VIEWPOINT_KEYS.iter()
+            .position(|&viewpoint_key| viewpoint_key == key)
+            .and_then(|index| self.viewpoints.get(index))
+        {
+            self.transition_to(current, viewpoint);
+        }
+    }
+
+    // Camera of the frame while a path plays, a finished path yields its last key once
+    pub fn update(&mut self, time: &FrameTime) -> Option<CameraKey> {
+        let path = self.playing.as_ref()?;
+        let elapsed = time.total_secs() - *self.start.get_or_insert(time.total_secs());
+        let key = path.sample(elapsed);
+        if !path.is_looping() && elapsed >= path.duration() {
+            self.playing = None;
+        }
+        key
+    }
+}
+
+impl Default for CameraTimeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{point3, vec3};
+
+    use super::*;
+
+    fn key(x: f32, z: f32) -> CameraKey {
+        CameraKey::new(point3(x, 0.0, z), point3(0.0, 0.0, 0.0), Vector3::unit_y(), Rad(1.0))
+    }
+
+    fn near(a: Point3<f32>, b: Point3<f32>) -> bool {
+        (a - b).magnitude() < 1e-4
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_the_inner_points() {
+        assert!((catmull_rom(0.0, 1.0, 3.0, 4.0, 0.0) - 1.0_f32).abs() < 1e-6);
+        assert!((catmull_rom(0.0, 1.0, 3.0, 4.0, 1.0) - 3.0_f32).abs() < 1e-6);
+        assert!((catmull_rom(0.0, 1.0, 2.0, 3.0, 0.5) - 1.5_f32).abs() < 1e-6);
+    }
+
+    #[test]
+    fn easing_keeps_the_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.apply(-1.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(2.0), 1.0, "{easing:?}");
+        }
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn keys_are_hit_and_interpolated() {
+        let path = CameraPath::new().with_key(2.0, key(2.0, 1.0)).with_key(0.0, key(0.0, 1.0));
+        assert!(near(path.sample(0.0).unwrap().eye, point3(0.0, 0.0, 1.0)));
+        assert!(near(path.sample(1.0).unwrap().eye, point3(1.0, 0.0, 1.0)));
+        assert!(near(path.sample(2.0).unwrap().eye, point3(2.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn clamped_outside_the_keys() {
+        let path = CameraPath::new().with_key(1.0, key(0.0, 1.0)).with_key(2.0, key(2.0, 1.0));
+        assert_eq!(path.sample(-5.0), Some(key(0.0, 1.0)));
+        assert_eq!(path.sample(5.0), Some(key(2.0, 1.0)));
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let path = CameraPath::new()
+            .with_key(0.0, key(0.0, 1.0))
+            .with_key(1.0, key(2.0, 1.0))
+            .with_loop(true);
+        assert_eq!(path.sample(2.5), path.sample(0.5));
+        assert_eq!(path.sample(-0.5), path.sample(0.5));
+    }
+
+    #[test]
+    fn empty_and_single_key_paths() {
+        assert_eq!(CameraPath::new().sample(0.0), None);
+        assert_eq!(CameraPath::new().duration(), 0.0);
+        let single = CameraPath::new().with_key(1.0, key(1.0, 1.0));
+        assert_eq!(single.sample(0.0), Some(key(1.0, 1.0)));
+        assert_eq!(single.sample(3.0), Some(key(1.0, 1.0)));
+    }
+
+    #[test]
+    fn transition_orbits_the_target() {
+        let path = CameraPath::transition(key(0.0, 2.0), key(2.0, 0.0), 1.0);
+        let middle = path.sample(0.5).unwrap();
+        assert!((middle.eye.to_vec().magnitude() - 2.0).abs() < 1e-4);
+        assert!(near(middle.eye, Point3::from_vec(vec3(1.0, 0.0, 1.0).normalize() * 2.0)));
+        assert!(near(path.sample(1.0).unwrap().eye, point3(2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn timeline_moves_to_a_viewpoint_and_stops() {
+        let mut timeline = CameraTimeline::new().with_viewpoints([key(0.0, 2.0), key(2.0, 0.0)]);
+        let press = InputEvent::Key { key: VirtualKeyCode::Key2, state: ElementState::Pressed, modifiers: Default::default() };
+        timeline.input(&press, key(0.0, 2.0));
+        assert!(timeline.is_playing());
+        let frame = |secs: f32| FrameTime { total: core::time::Duration::from_secs_f32(secs), ..FrameTime::default() };
+        assert!(near(timeline.update(&frame(0.0)).unwrap().eye, point3(0.0, 0.0, 2.0)));
+        assert!(near(timeline.update(&frame(1.0)).unwrap().eye, point3(2.0, 0.0, 0.0)));
+        assert!(!timeline.is_playing());
+        assert_eq!(timeline.update(&frame(2.0)), None);
+    }
+}
@@ -0,0 +1,199 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use winit::event::VirtualKeyCode;
+
+use crate::InputEvent;
+
+//
+// FrameTime
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameTime {
+    pub delta: Duration,
+    // Wall-clock time since the previous frame, neither scaled nor paused
+    pub real_delta: Duration,
+    pub total: Duration,
+    pub frame: u64,
+}
+
+impl FrameTime {
+    #[must_use]
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    #[must_use]
+    pub fn real_delta_secs(&self) -> f32 {
+        self.real_delta.as_secs_f32()
+    }
+
+    #[must_use]
+    pub fn total_secs(&self) -> f32 {
+        self.total.as_secs_f32()
+    }
+}
+
+//
+// FrameClock
+
+#[must_use]
+pub struct FrameClock {
+    last: Option<Instant>,
+    time: FrameTime,
+    frames: u64,
+    scale: f64,
+    paused: bool,
+    steps: u32,
+    fixed_step: Option<Duration>,
+    accumulator: Duration,
+    // Wall-clock time not yet handed to a frame
+    real: Duration,
+    max_steps: u32,
+    hotkeys: bool,
+}
+
+impl FrameClock {
+    const DEFAULT_STEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+    const SCALE_FACTOR: f64 = 2.0;
+
+    pub fn new() -> Self {
+        Self {
+            last: None,
+            time: FrameTime::default(),
+            frames: 0,
+            scale: 1.0,
+            paused: false,
+            steps: 0,
+            fixed_step: None,
+            accumulator: Duration::ZERO,
+            real: Duration::ZERO,
+            max_steps: 8,
+            hotkeys: false,
+        }
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.set_scale(scale);
+        self
+    }
+
+    pub fn with_fixed_step(mut self, step: Duration, max_steps: u32) -> Self {
+        self.fixed_step = Some(step);
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    // Space pauses, period steps a paused clock, minus and equals halve and double the time scale
+    pub fn with_hotkeys(mut self, hotkeys: bool) -> Self {
+        self.hotkeys = hotkeys;
+        self
+    }
+
+    #[must_use]
+    pub fn has_hotkeys(&self) -> bool {
+        self.hotkeys
+    }
+
+    #[must_use]
+    pub fn time(&self) -> FrameTime {
+        self.time
+    }
+
+    #[must_use]
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale.max(0.0);
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub fn input(&mut self, event: &InputEvent) {
+        if event.is_pressed(VirtualKeyCode::Space) {
+            self.paused = !self.paused;
+        } else if event.is_pressed(VirtualKeyCode::Period) {
+            self.pause();
+            self.step();
+        } else if event.is_pressed(VirtualKeyCode::Minus) {
+            self.set_scale(self.scale / Self::SCALE_FACTOR);
+        } else if event.is_pressed(VirtualKeyCode::Equals) {
+            self.set_scale(self.scale * Self::SCALE_FACTOR);
+        }
+    }
+
+    pub fn tick(&mut self) -> Vec<FrameTime> {
+        let now = Instant::now();
+        let elapsed = self.last.map_or(Duration::ZERO, |last| now - last);
+        self.last = Some(now);
+        self.advance(elapsed)
+    }
+
+    pub fn advance(&mut self, elapsed: Duration) -> Vec<FrameTime> {
+        let step = self.fixed_step.unwrap_or(Self::DEFAULT_STEP);
+        self.real += elapsed;
+        if self.paused {
+            if self.steps > 0 {
+                self.steps -= 1;
+                return vec![self.next(step)];
+            }
+            return vec![self.next(Duration::ZERO)];
+        }
+
+        let scaled = elapsed.mul_f64(self.scale);
+        match self.fixed_step {
+            None => vec![self.next(scaled)],
+            Some(fixed) => {
+                self.accumulator += scaled;
+                let mut frames = vec![];
+                while self.accumulator >= fixed && frames.len() < self.max_steps as usize {
+                    self.accumulator -= fixed;
+                    frames.push(self.next(fixed));
+                }
+                if frames.len() == self.max_steps as usize {
+                    self.accumulator = Duration::ZERO;
+                }
+                frames
+            }
+        }
+    }
+
+    fn next(&mut self, delta: Duration) -> FrameTime {
+        self.time = FrameTime {
+            delta,
+            real_delta: core::mem::take(&mut self.real),
+            total: self.time.total + delta,
+            frame: self.frames,
+        };
+        self.frames += 1;
+        self.time
+    }
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,152 @@
+//
+// DeviceConfiguration
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct DeviceConfiguration {
+    pub backends: wgpu::Backends,
+    pub power_preference: wgpu::PowerPreference,
+    pub force_fallback_adapter: bool,
+    pub present_modes: Vec<wgpu::PresentMode>,
+    // Empty for opaque, or blended for a transparent window
+    pub alpha_modes: Vec<wgpu::CompositeAlphaMode>,
+    pub srgb: bool,
+    pub optional_features: wgpu::Features,
+}
+
+impl DeviceConfiguration {
+    pub fn new() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            present_modes: vec![wgpu::PresentMode::Mailbox],
+            alpha_modes: vec![],
+            srgb: true,
+            optional_features: wgpu::Features::empty(),
+        }
+    }
+
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    pub fn with_power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    pub fn with_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    pub fn with_present_mode(self, present_mode: wgpu::PresentMode) -> Self {
+        self.with_present_modes(vec![present_mode])
+    }
+
+    pub fn with_present_modes(mut self, present_modes: Vec<wgpu::PresentMode>) -> Self {
+        self.present_modes = present_modes;
+        self
+    }
+
+    pub fn with_alpha_modes(mut self, alpha_modes: Vec<wgpu::CompositeAlphaMode>) -> Self {
+        self.alpha_modes = alpha_modes;
+        self
+    }
+
+    pub fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    pub fn with_optional_features(mut self, optional_features: wgpu::Features) -> Self {
+        self.optional_features = optional_features;
+        self
+    }
+
+    pub(crate) fn device_descriptor(&self, adapter: &wgpu::Adapter) -> wgpu::DeviceDescriptor<'static> {
+        wgpu::DeviceDescriptor {
+            features: adapter.features() & self.optional_features,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn adapter_options<'a>(&self, surface: Option<&'a wgpu::Surface>) -> wgpu::RequestAdapterOptions<'a> {
+        wgpu::RequestAdapterOptions {
+            power_preference: self.power_preference,
+            force_fallback_adapter: self.force_fallback_adapter,
+            compatible_surface: surface,
+        }
+    }
+
+    pub(crate) fn present_mode(&self, capabilities: &wgpu::SurfaceCapabilities) -> wgpu::PresentMode {
+        self.present_modes.iter()
+            .find(|&mode| capabilities.present_modes.contains(mode))
+            .copied()
+            .unwrap_or(wgpu::PresentMode::Fifo)
+    }
+
+    pub(crate) fn alpha_mode(&self, capabilities: &wgpu::SurfaceCapabilities) -> wgpu::CompositeAlphaMode {
+        let opaque = [wgpu::CompositeAlphaMode::Opaque];
+        let alpha_modes = if self.alpha_modes.is_empty() { &opaque[..] } else { &self.alpha_modes[..] };
+        alpha_modes.iter()
+            .find(|&mode| capabilities.alpha_modes.contains(mode))
+            .or_else(|| capabilities.alpha_modes.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn offscreen_format(&self) -> wgpu::TextureFormat {
+        if self.srgb {
+            wgpu::TextureFormat::Bgra8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Bgra8Unorm
+        }
+    }
+
+    pub(crate) fn format(&self, capabilities: &wgpu::SurfaceCapabilities) -> wgpu::TextureFormat {
+        let formats = &capabilities.formats;
+        formats.iter()
+            .find(|format| format.is_srgb() == self.srgb)
+            .or_else(|| formats.first())
+            .copied()
+            .expect("at least one compatible format")
+    }
+}
+
+impl Default for DeviceConfiguration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//
+// AdapterDescription
+
+#[derive(Clone, Debug)]
+pub struct AdapterDescription {
+    pub info: wgpu::AdapterInfo,
+    pub features: wgpu::Features,
+    pub limits: wgpu::Limits,
+}
+
+#[must_use]
+pub fn adapters(backends: wgpu::Backends) -> Vec<AdapterDescription> {
+    let instance = DeviceConfiguration::new().with_backends(backends).instance();
+    instance.enumerate_adapters(backends)
+        .map(|adapter| AdapterDescription {
+            info: adapter.get_info(),
+            features: adapter.features(),
+            limits: adapter.limits(),
+        })
+        .collect()
+}
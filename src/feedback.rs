@@ -0,0 +1,221 @@
+use anyhow::{bail, Context, Result};
+use image::io::Reader as ImageReader;
+
+use crate::bindings::{sampled_bindings, Binding};
+use crate::TextureInfo;
+use crate::webgpu::WebGPUDevice;
+
+//
+// FeedbackConfiguration
+
+#[must_use]
+pub struct FeedbackConfiguration {
+    name: String,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    filter: wgpu::FilterMode,
+    address_modes: (wgpu::AddressMode, wgpu::AddressMode),
+    seed: Option<String>,
+}
+
+impl FeedbackConfiguration {
+    pub fn new(name: &str, width: u32, height: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            width,
+            height,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            filter: wgpu::FilterMode::Linear,
+            address_modes: (wgpu::AddressMode::Repeat, wgpu::AddressMode::Repeat),
+            seed: None,
+        }
+    }
+
+    pub fn seeded(name: &str, seed: &TextureInfo) -> Result<Self> {
+        let (width, height) = image::image_dimensions(seed.file.as_str())
+            .context(format!("Seed file '{}' missing", seed.file))?;
+        Ok(Self {
+            address_modes: (seed.u_mode, seed.v_mode),
+            seed: Some(seed.file.clone()),
+            ..Self::new(name, width, height)
+        })
+    }
+
+    pub fn with_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: wgpu::FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_address_modes(mut self, u_mode: wgpu::AddressMode, v_mode: wgpu::AddressMode) -> Self {
+        self.address_modes = (u_mode, v_mode);
+        self
+    }
+}
+
+//
+// Feedback
+
+pub(crate) struct Feedback {
+    pub(crate) name: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) format: wgpu::TextureFormat,
+    textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+    sampler: wgpu::Sampler,
+}
+
+impl Feedback {
+    fn new(wg: &WebGPUDevice, conf: FeedbackConfiguration, storage: bool) -> Result<Self> {
+        let mut usage = wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC;
+        if storage {
+            let features = conf.format.guaranteed_format_features(wg.device.features());
+            if !features.allowed_usages.contains(wgpu::TextureUsages::STORAGE_BINDING) {
+                bail!("Feedback '{}' format {:?} cannot be written by a compute pass", conf.name, conf.format);
+            }
+            usage |= wgpu::TextureUsages::STORAGE_BINDING;
+        }
+        let textures = [0, 1].map(|index| {
+            let label = format!("Feedback {} {index}", conf.name);
+            wg.create_texture(&label, conf.width, conf.height, usage, conf.format)
+        });
+        let views = [0, 1].map(|index| textures[index].create_view(&wgpu::TextureViewDescriptor::default()));
+        let sampler = wg.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: conf.address_modes.0,
+            address_mode_v: conf.address_modes.1,
+            mag_filter: conf.filter,
+            min_filter: conf.filter,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let feedback = Self {
+            name: conf.name,
+            width: conf.width,
+            height: conf.height,
+            format: conf.format,
+            textures,
+            views,
+            sampler,
+        };
+        if let Some(file) = conf.seed.as_ref() {
+            feedback.seed(wg, file)?;
+        }
+        Ok(feedback)
+    }
+
+    fn seed(&self, wg: &WebGPUDevice, file: &str) -> Result<()> {
+        use wgpu::TextureFormat as F;
+
+        let image = ImageReader::open(file)
+            .context(format!("Seed file '{file}' missing"))?.decode()?;
+        let (data, bytes_per_pixel) = match self.format {
+            F::Rgba8Unorm | F::Rgba8UnormSrgb => (image.to_rgba8().into_raw(), 4),
+            F::Rgba16Float => (
+                image.to_rgba32f().into_raw().into_iter()
+                    .flat_map(|value| f32_to_f16(value).to_le_bytes())
+                    .collect(),
+                8,
+            ),
+            F::Rgba32Float => (
+                image.to_rgba32f().into_raw().into_iter()
+                    .flat_map(f32::to_le_bytes)
+                    .collect(),
+                16,
+            ),
+            format => bail!("Unsupported seed format {format:?} for feedback '{}'", self.name),
+        };
+
+        for texture in &self.textures {
+            wg.queue.write_texture(
+                texture.as_image_copy(),
+                &data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_pixel * self.width),
+                    rows_per_image: Some(self.height),
+                },
+                wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn target(&self, phase: usize) -> &wgpu::TextureView {
+        &self.views[Self::written(phase)]
+    }
+
+    fn written(phase: usize) -> usize {
+        (phase + 1) % 2
+    }
+
+    pub(crate) fn bindings(&self) -> [Binding<'_>; 2] {
+        sampled_bindings(self.views.iter().collect(), &self.sampler, self.format)
+    }
+
+    // Texture view read in the given phase: the one written earlier in this frame,
+    // otherwise the one written by the previous frame.
+    pub(crate) fn view_index(phase: usize, written: bool) -> usize {
+        if written { Self::written(phase) } else { phase % 2 }
+    }
+}
+
+//
+// Feedbacks
+
+#[derive(Default)]
+pub(crate) struct Feedbacks {
+    feedbacks: Vec<Feedback>,
+}
+
+impl Feedbacks {
+    pub(crate) const PHASES: usize = 2;
+
+    // Feedbacks named in storage are also bound as storage textures, for compute passes
+    pub(crate) fn new(wg: &WebGPUDevice, confs: Vec<FeedbackConfiguration>, storage: &[String]) -> Result<Self> {
+        let mut feedbacks: Vec<Feedback> = vec![];
+        for conf in confs {
+            if feedbacks.iter().any(|feedback| feedback.name == conf.name) {
+                bail!("Duplicate feedback texture '{}'", conf.name);
+            }
+            let written = storage.contains(&conf.name);
+            feedbacks.push(Feedback::new(wg, conf, written)?);
+        }
+        Ok(Self { feedbacks })
+    }
+
+    pub(crate) fn index(&self, name: &str) -> Option<usize> {
+        self.feedbacks.iter().position(|feedback| feedback.name == name)
+    }
+
+    #[allow(clippy::indexing_slicing)]
+    pub(crate) fn get(&self, index: usize) -> &Feedback {
+        &self.feedbacks[index]
+    }
+}
+
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16 & 0x8000) as u16;
+    let exponent = (bits >> 23 & 0xff) as i32 - 127 + 15;
+    let mantissa = bits & 0x7f_ffff;
+    if value.is_nan() {
+        sign | 0x7e00
+    } else if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        let shift = 14 - exponent;
+        if shift > 24 { sign } else { sign | ((mantissa | 0x80_0000) >> shift) as u16 }
+    } else {
+        sign | (exponent as u16) << 10 | (mantissa >> 13) as u16
+    }
+}
@@ -0,0 +1,105 @@
+use bytemuck::{Pod, Zeroable};
+use winit::event::MouseButton;
+
+use crate::{Content, FrameTime, InputState, Tile, Uniform, UniformInfo, UniformsConfiguration};
+
+//
+// FrameUniform
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+pub struct FrameUniform {
+    pub tile: [f32; 4],
+    pub resolution: [f32; 2],
+    pub cursor: [f32; 2],
+    pub time: f32,
+    pub delta: f32,
+    pub frame: u32,
+    pub buttons: u32,
+}
+
+impl UniformInfo for FrameUniform {
+    const STRUCT_NAME: &'static str = "FrameUniform";
+    const BINDING_NAME: &'static str = "frame_u";
+    const ATTRIBUTES: &'static [(&'static str, &'static str)] = &[
+        ("tile", "vec4<f32>"),
+        ("resolution", "vec2<f32>"),
+        ("cursor", "vec2<f32>"),
+        ("time", "f32"),
+        ("delta", "f32"),
+        ("frame", "u32"),
+        ("buttons", "u32"),
+    ];
+}
+
+impl FrameUniform {
+    pub const LEFT_BUTTON: u32 = 1;
+    pub const RIGHT_BUTTON: u32 = 2;
+    pub const MIDDLE_BUTTON: u32 = 4;
+}
+
+//
+// TargetUniform
+
+// Pixel size of the area a pipeline draws to, unlike the window-sized frame resolution
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+pub struct TargetUniform {
+    pub size: [f32; 2],
+}
+
+impl UniformInfo for TargetUniform {
+    const STRUCT_NAME: &'static str = "TargetUniform";
+    const BINDING_NAME: &'static str = "target_u";
+    const ATTRIBUTES: &'static [(&'static str, &'static str)] = &[
+        ("size", "vec2<f32>"),
+    ];
+}
+
+//
+// FrameGlobals
+
+pub(crate) struct FrameGlobals {
+    uniform: Uniform<FrameUniform>,
+}
+
+impl FrameGlobals {
+    pub(crate) fn new(uniforms: &mut UniformsConfiguration) -> Self {
+        let value = FrameUniform { tile: [0.0, 0.0, 1.0, 1.0], ..FrameUniform::default() };
+        let uniform = uniforms
+            .add("Frame", value, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE)
+            .value();
+        Self { uniform }
+    }
+}
+
+impl Content<()> for FrameGlobals {
+    #[allow(clippy::cast_precision_loss)]
+    fn resize(&mut self, _context: (), width: u32, height: u32) {
+        self.uniform.as_mut().resolution = [width as f32, height as f32];
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn update(&mut self, _context: (), time: &FrameTime, input: &InputState) {
+        let buttons = [
+            (MouseButton::Left, FrameUniform::LEFT_BUTTON),
+            (MouseButton::Right, FrameUniform::RIGHT_BUTTON),
+            (MouseButton::Middle, FrameUniform::MIDDLE_BUTTON),
+        ];
+        let cursor = input.cursor();
+
+        let mut frame = self.uniform.as_mut();
+        frame.time = time.total_secs();
+        frame.delta = time.delta_secs();
+        frame.frame = time.frame as u32;
+        frame.cursor = [cursor.x as f32, cursor.y as f32];
+        frame.buttons = buttons.iter()
+            .filter(|&&(button, _)| input.is_button_down(button))
+            .map(|&(_, bit)| bit)
+            .sum();
+    }
+
+    fn tile(&mut self, _context: (), tile: &Tile) {
+        self.uniform.as_mut().tile = [tile.x, tile.y, tile.width, tile.height];
+    }
+}
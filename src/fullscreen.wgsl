@@ -0,0 +1,17 @@
+struct FullscreenOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) target_uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> FullscreenOutput {
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    let target_uv = vec2<f32>(corner.x, 1.0 - corner.y);
+
+    var output: FullscreenOutput;
+    output.position = vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+    output.uv = frame_u.tile.xy + target_uv * frame_u.tile.zw;
+    output.target_uv = target_uv;
+    return output;
+}
@@ -0,0 +1,294 @@
+use anyhow::{bail, Context, Result};
+
+use crate::RenderPassConfiguration;
+use crate::attachments::Attachments;
+use crate::bindings::{sampled_declaration, Binding};
+use crate::feedback::{Feedback, Feedbacks};
+
+//
+// Resource
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Resource {
+    Surface,
+    Feedback(usize),
+    Attachment(usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Input {
+    pub(crate) resource: Resource,
+    pub(crate) written: bool,
+}
+
+//
+// GraphPass
+
+pub(crate) struct GraphPass {
+    pub(crate) conf: RenderPassConfiguration,
+    pub(crate) target: Resource,
+    // Inputs of each pipeline, followed by those of the compute shader
+    pub(crate) inputs: Vec<Vec<Input>>,
+}
+
+//
+// Resources
+
+pub(crate) struct Resources {
+    pub(crate) feedbacks: Feedbacks,
+    pub(crate) attachments: Attachments,
+    pub(crate) surface_format: wgpu::TextureFormat,
+}
+
+impl Resources {
+    fn resolve(&self, name: &str) -> Result<Resource> {
+        let feedback = self.feedbacks.index(name).map(Resource::Feedback);
+        let attachment = self.attachments.index(name).map(Resource::Attachment);
+        match (feedback, attachment) {
+            (Some(_), Some(_)) => bail!("Texture '{name}' is both a feedback and an attachment"),
+            (Some(resource), None) | (None, Some(resource)) => Ok(resource),
+            (None, None) => bail!("Unknown texture '{name}'"),
+        }
+    }
+
+    fn name(&self, resource: Resource) -> &str {
+        match resource {
+            Resource::Surface => "surface",
+            Resource::Feedback(index) => &self.feedbacks.get(index).name,
+            Resource::Attachment(index) => &self.attachments.get(index).name,
+        }
+    }
+
+    pub(crate) fn format(&self, resource: Resource) -> wgpu::TextureFormat {
+        match resource {
+            Resource::Surface => self.surface_format,
+            Resource::Feedback(index) => self.feedbacks.get(index).format,
+            Resource::Attachment(index) => self.attachments.get(index).format,
+        }
+    }
+
+    // Surface passes draw at the size of the frame being rendered
+    pub(crate) fn size(&self, resource: Resource, width: u32, height: u32) -> (u32, u32) {
+        match resource {
+            Resource::Surface => (width, height),
+            Resource::Feedback(index) => {
+                let feedback = self.feedbacks.get(index);
+                (feedback.width, feedback.height)
+            }
+            Resource::Attachment(index) => {
+                let (_, width, height) = self.attachments.get(index).target();
+                (width, height)
+            }
+        }
+    }
+
+    pub(crate) fn bindings(&self, resource: Resource) -> [Binding<'_>; 2] {
+        match resource {
+            Resource::Surface => unreachable!("Surface is never sampled"),
+            Resource::Feedback(index) => self.feedbacks.get(index).bindings(),
+            Resource::Attachment(index) => self.attachments.get(index).bindings(),
+        }
+    }
+
+    pub(crate) fn view_index(&self, input: &Input, phase: usize) -> usize {
+        match input.resource {
+            Resource::Feedback(_) => Feedback::view_index(phase, input.written),
+            Resource::Surface | Resource::Attachment(_) => 0,
+        }
+    }
+
+    pub(crate) fn declaration(&self, resource: Resource, group: u32, binding: usize) -> String {
+        sampled_declaration(self.name(resource), self.format(resource), group, binding)
+    }
+
+    // Orders passes so that every texture is written before it is read. Buffers are not graph
+    // resources: vertex, index and uniform buffers belong to a single pipeline.
+    pub(crate) fn order(&self, passes: Vec<RenderPassConfiguration>) -> Result<Vec<GraphPass>> {
+        let mut confs = vec![];
+        let mut nodes = vec![];
+        for (index, mut conf) in passes.into_iter().enumerate() {
+            let label = conf.label.get_or_insert_with(|| format!("Pass {index}")).clone();
+            let target = match conf.target.as_deref() {
+                Some(name) => self.resolve(name).context(format!("Pass '{label}' target"))?,
+                None => Resource::Surface,
+            };
+            if conf.compute.is_some() && !matches!(target, Resource::Feedback(_)) {
+                bail!("Compute pass '{label}' must target a feedback texture");
+            }
+            let reads = conf.pipelines.iter()
+                .map(|pipeline| &pipeline.inputs)
+                .chain(conf.compute.iter().map(|compute| &compute.inputs))
+                .map(|inputs| inputs.iter()
+                    .map(|name| self.resolve(name))
+                    .collect::<Result<Vec<_>>>())
+                .collect::<Result<Vec<_>>>()
+                .context(format!("Pass '{label}' inputs"))?;
+            confs.push(Some(conf));
+            nodes.push(Node { label, target, reads });
+        }
+
+        let order = schedule(&nodes, |resource| self.name(resource))?;
+        Ok(order.into_iter()
+            .filter_map(|index| Some((index, confs.get_mut(index)?.take()?, nodes.get(index)?)))
+            .map(|(index, conf, node)| GraphPass {
+                conf,
+                target: node.target,
+                inputs: node.reads.iter()
+                    .map(|reads| reads.iter()
+                        .map(|&resource| Input { resource, written: writers(&nodes, resource, index).next().is_some() })
+                        .collect())
+                    .collect(),
+            })
+            .collect())
+    }
+}
+
+//
+// Node
+
+// A pass as seen by the scheduler, with the inputs of each of its pipelines
+struct Node {
+    label: String,
+    target: Resource,
+    reads: Vec<Vec<Resource>>,
+}
+
+fn writers(nodes: &[Node], resource: Resource, reader: usize) -> impl Iterator<Item = usize> + '_ {
+    nodes.iter().enumerate()
+        .filter(move |&(index, node)| node.target == resource && index != reader)
+        .map(|(index, _)| index)
+}
+
+// Passes writing the same target keep their insertion order, as do otherwise independent passes.
+// A pass reading the feedback it writes sees the previous frame, any other reader sees the current one.
+fn schedule<'a>(nodes: &[Node], name: impl Fn(Resource) -> &'a str) -> Result<Vec<usize>> {
+    let mut dependencies = vec![];
+    for (index, node) in nodes.iter().enumerate() {
+        let label = &node.label;
+        let mut depends = writers(nodes, node.target, index).filter(|&writer| writer < index).collect::<Vec<_>>();
+        for &resource in node.reads.iter().flatten() {
+            if let Resource::Attachment(_) = resource {
+                if resource == node.target {
+                    bail!("Pass '{label}' reads its own target '{}'", name(resource));
+                }
+                if writers(nodes, resource, index).next().is_none() {
+                    bail!("Pass '{label}' reads '{}' but no pass writes it", name(resource));
+                }
+            }
+            depends.extend(writers(nodes, resource, index));
+        }
+        dependencies.push(depends);
+    }
+
+    let mut remaining = (0..nodes.len()).collect::<Vec<_>>();
+    let mut order: Vec<usize> = vec![];
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|&index| dependencies.get(index)
+            .is_some_and(|depends| depends.iter().all(|depend| order.contains(depend))));
+        let Some(position) = ready else {
+            let labels = remaining.iter()
+                .filter_map(|&index| nodes.get(index))
+                .map(|node| format!("'{}'", node.label))
+                .collect::<Vec<_>>();
+            bail!("Render graph has a cycle between passes {}", labels.join(", "));
+        };
+        order.push(remaining.remove(position));
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: Resource = Resource::Attachment(0);
+    const BLOOM: Resource = Resource::Attachment(1);
+    const LIFE: Resource = Resource::Feedback(0);
+
+    fn node(label: &str, target: Resource, reads: &[Resource]) -> Node {
+        Node { label: label.to_owned(), target, reads: vec![reads.to_vec()] }
+    }
+
+    fn name(resource: Resource) -> &'static str {
+        match resource {
+            Resource::Surface => "surface",
+            Resource::Feedback(_) => "life",
+            Resource::Attachment(0) => "scene",
+            Resource::Attachment(_) => "bloom",
+        }
+    }
+
+    fn error(nodes: &[Node]) -> String {
+        schedule(nodes, name).expect_err("Scheduling should fail").to_string()
+    }
+
+    #[test]
+    fn producers_run_before_readers() {
+        let nodes = [
+            node("Composite", Resource::Surface, &[SCENE, BLOOM]),
+            node("Bloom", BLOOM, &[SCENE]),
+            node("Scene", SCENE, &[]),
+        ];
+        assert_eq!(schedule(&nodes, name).unwrap(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn independent_passes_keep_insertion_order() {
+        let nodes = [
+            node("Faces", Resource::Surface, &[]),
+            node("Scene", SCENE, &[]),
+            node("Lines", Resource::Surface, &[]),
+            node("Overlay", Resource::Surface, &[SCENE]),
+        ];
+        assert_eq!(schedule(&nodes, name).unwrap(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn same_target_writers_keep_insertion_order() {
+        let nodes = [
+            node("Show", Resource::Surface, &[SCENE]),
+            node("Background", SCENE, &[]),
+            node("Foreground", SCENE, &[]),
+        ];
+        assert_eq!(schedule(&nodes, name).unwrap(), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn feedback_reads_its_previous_frame() {
+        let nodes = [
+            node("Display", Resource::Surface, &[LIFE]),
+            node("Step", LIFE, &[LIFE]),
+        ];
+        assert_eq!(schedule(&nodes, name).unwrap(), vec![1, 0]);
+        assert_eq!(writers(&nodes, LIFE, 1).count(), 0);
+        assert_eq!(writers(&nodes, LIFE, 0).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn unwritten_feedback_is_seed_only() {
+        let nodes = [node("Display", Resource::Surface, &[LIFE])];
+        assert_eq!(schedule(&nodes, name).unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let nodes = [
+            node("Show", Resource::Surface, &[]),
+            node("Scene", SCENE, &[BLOOM]),
+            node("Bloom", BLOOM, &[SCENE]),
+        ];
+        assert_eq!(error(&nodes), "Render graph has a cycle between passes 'Scene', 'Bloom'");
+    }
+
+    #[test]
+    fn missing_producers_are_rejected() {
+        let nodes = [node("Composite", Resource::Surface, &[BLOOM])];
+        assert_eq!(error(&nodes), "Pass 'Composite' reads 'bloom' but no pass writes it");
+    }
+
+    #[test]
+    fn reading_own_attachment_is_rejected() {
+        let nodes = [node("Blur", BLOOM, &[BLOOM])];
+        assert_eq!(error(&nodes), "Pass 'Blur' reads its own target 'bloom'");
+    }
+}
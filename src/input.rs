@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode};
+
+//
+// InputEvent
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum InputEvent {
+    Key {
+        key: VirtualKeyCode,
+        state: ElementState,
+        modifiers: ModifiersState,
+    },
+    MouseButton {
+        button: MouseButton,
+        state: ElementState,
+        modifiers: ModifiersState,
+    },
+    CursorMoved {
+        position: PhysicalPosition<f64>,
+        normalized: [f32; 2],
+    },
+    MouseMotion {
+        delta: (f64, f64),
+    },
+    Scroll {
+        delta: [f32; 2],
+    },
+    // Touchpad pinch, positive when zooming in
+    Magnify {
+        delta: f32,
+    },
+    Focused(bool),
+}
+
+impl InputEvent {
+    const PIXELS_PER_LINE: f32 = 20.0;
+
+    #[must_use]
+    pub fn is_pressed(&self, expected: VirtualKeyCode) -> bool {
+        matches!(*self, InputEvent::Key { key, state: ElementState::Pressed, .. } if key == expected)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn scroll(delta: MouseScrollDelta) -> Self {
+        let delta = match delta {
+            MouseScrollDelta::LineDelta(x, y) => [x, y],
+            MouseScrollDelta::PixelDelta(position) => [
+                position.x as f32 / Self::PIXELS_PER_LINE,
+                position.y as f32 / Self::PIXELS_PER_LINE,
+            ],
+        };
+        InputEvent::Scroll { delta }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn cursor(position: PhysicalPosition<f64>, width: u32, height: u32) -> Self {
+        let normalized = [
+            (position.x / f64::from(width.max(1))) as f32,
+            (position.y / f64::from(height.max(1))) as f32,
+        ];
+        InputEvent::CursorMoved { position, normalized }
+    }
+}
+
+//
+// InputState
+
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys: HashSet<VirtualKeyCode>,
+    buttons: HashSet<MouseButton>,
+    modifiers: ModifiersState,
+    cursor: PhysicalPosition<f64>,
+    normalized_cursor: [f32; 2],
+    focused: bool,
+}
+
+impl InputState {
+    #[must_use]
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+        self.keys.contains(&key)
+    }
+
+    #[must_use]
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    #[must_use]
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    #[must_use]
+    pub fn cursor(&self) -> PhysicalPosition<f64> {
+        self.cursor
+    }
+
+    #[must_use]
+    pub fn normalized_cursor(&self) -> [f32; 2] {
+        self.normalized_cursor
+    }
+
+    #[must_use]
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub(crate) fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    pub(crate) fn apply(&mut self, event: &InputEvent) {
+        match *event {
+            InputEvent::Key { key, state, .. } => Self::toggle(&mut self.keys, key, state),
+            InputEvent::MouseButton { button, state, .. } => Self::toggle(&mut self.buttons, button, state),
+            InputEvent::CursorMoved { position, normalized } => {
+                self.cursor = position;
+                self.normalized_cursor = normalized;
+            }
+            InputEvent::Focused(focused) => {
+                self.focused = focused;
+                if !focused {
+                    self.keys.clear();
+                    self.buttons.clear();
+                }
+            }
+            InputEvent::MouseMotion { .. } | InputEvent::Scroll { .. } | InputEvent::Magnify { .. } => {}
+        }
+    }
+
+    fn toggle<T: Eq + core::hash::Hash>(set: &mut HashSet<T>, value: T, state: ElementState) {
+        match state {
+            ElementState::Pressed => set.insert(value),
+            ElementState::Released => set.remove(&value),
+        };
+    }
+}
@@ -1,18 +1,55 @@
+use core::time::Duration;
+
 use boxed::FuncBox;
 
+use crate::frame::FrameGlobals;
+use crate::screenshot::ScreenshotKey;
+use crate::transforms::Projection;
+
+pub use crate::attachments::AttachmentConfiguration;
 pub use crate::bindings::TextureInfo;
+pub use crate::camera::{FlyCamera, OrbitCamera};
+pub use crate::camera_path::{CameraKey, CameraPath, CameraTimeline, Easing};
 pub use crate::buffer::*;
+pub use crate::clock::{FrameClock, FrameTime};
+pub use crate::device::{adapters, AdapterDescription, DeviceConfiguration};
+pub use crate::feedback::FeedbackConfiguration;
+pub use crate::frame::{FrameUniform, TargetUniform};
+pub use crate::input::{InputEvent, InputState};
+pub use crate::post::{PostProcessing, ToneMapping};
+pub use crate::poster::{Poster, Tile};
+pub use crate::profiler::{PassTiming, PipelineTiming, ProfileReport, Profiler};
+pub use crate::recording::Recording;
+pub use crate::screenshot::Screenshot;
+pub use crate::ssao::AmbientOcclusion;
 pub use crate::uniforms::*;
+pub use crate::viewport::Viewport;
 pub use crate::window_api::*;
 
 pub mod buffer;
 pub mod transforms;
 pub mod boxed;
+mod attachments;
+mod camera;
+mod camera_path;
+mod clock;
+mod device;
+mod feedback;
+mod frame;
+mod graph;
+mod input;
+mod post;
+mod poster;
+mod profiler;
+mod recording;
+mod screenshot;
+mod ssao;
 mod webgpu;
 pub mod window;
 mod window_api;
 mod bindings;
 mod uniforms;
+mod viewport;
 
 //
 // RenderConfiguration
@@ -21,11 +58,42 @@ mod uniforms;
 pub struct RenderConfiguration {
     render_passes: Vec<RenderPassConfiguration>,
     save_image: Option<String>,
+    device: DeviceConfiguration,
+    clock: Option<FrameClock>,
+    recording: Option<Recording>,
+    poster: Option<Poster>,
+    profiler: Option<Profiler>,
+    screenshot: Screenshot,
+    screenshot_key: Option<ScreenshotKey>,
+    uniforms: UniformsConfiguration,
+    listeners: Vec<Box<dyn Content<()>>>,
+    frame_uniform: bool,
+    feedbacks: Vec<FeedbackConfiguration>,
+    attachments: Vec<AttachmentConfiguration>,
+    post: Option<PostProcessing>,
+    ambient_occlusion: Option<AmbientOcclusion>,
 }
 
 impl RenderConfiguration {
     pub fn new() -> Self {
-        Self { render_passes: vec![], save_image: None }
+        Self {
+            render_passes: vec![],
+            save_image: None,
+            device: DeviceConfiguration::default(),
+            clock: None,
+            recording: None,
+            poster: None,
+            profiler: None,
+            screenshot: Screenshot::default(),
+            screenshot_key: None,
+            uniforms: UniformsConfiguration::default(),
+            listeners: vec![],
+            frame_uniform: false,
+            feedbacks: vec![],
+            attachments: vec![],
+            post: None,
+            ambient_occlusion: None,
+        }
     }
 
     #[allow(clippy::indexing_slicing)]
@@ -36,18 +104,115 @@ impl RenderConfiguration {
         &mut self.render_passes[last]
     }
 
+    #[allow(clippy::indexing_slicing)]
+    pub fn new_compute_pass(&mut self, compute: ComputeConfiguration) -> &mut RenderPassConfiguration {
+        let pass = RenderPassConfiguration::compute(compute);
+        self.render_passes.push(pass);
+        let last = self.render_passes.len() - 1;
+        &mut self.render_passes[last]
+    }
+
     pub fn add_pass(&mut self, pass: RenderPassConfiguration) -> &mut Self {
         self.render_passes.push(pass);
         self
     }
 
+    pub fn uniforms(&mut self) -> &mut UniformsConfiguration {
+        &mut self.uniforms
+    }
+
+    pub fn add_listener(&mut self, listener: Box<dyn Content<()>>) -> &mut Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    pub fn with_frame_uniform(&mut self) -> &mut Self {
+        if !self.frame_uniform {
+            self.frame_uniform = true;
+            let globals = FrameGlobals::new(&mut self.uniforms);
+            self.listeners.push(Box::new(globals));
+        }
+        self
+    }
+
+    pub fn add_feedback(&mut self, feedback: FeedbackConfiguration) -> &mut Self {
+        self.feedbacks.push(feedback);
+        self
+    }
+
+    pub fn add_attachment(&mut self, attachment: AttachmentConfiguration) -> &mut Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    pub fn with_post_processing(&mut self, post: PostProcessing) -> &mut Self {
+        self.post = Some(post);
+        self
+    }
+
+    pub fn with_ambient_occlusion(&mut self, ambient_occlusion: AmbientOcclusion) -> &mut Self {
+        self.ambient_occlusion = Some(ambient_occlusion);
+        self
+    }
+
+    pub fn with_device(&mut self, device: DeviceConfiguration) -> &mut Self {
+        self.device = device;
+        self
+    }
+
+    pub fn with_clock(&mut self, clock: FrameClock) -> &mut Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn record(&mut self, recording: Recording) -> &mut Self {
+        self.recording = Some(recording);
+        self
+    }
+
+    pub fn poster(&mut self, poster: Poster) -> &mut Self {
+        self.poster = Some(poster);
+        self
+    }
+
+    pub fn profile(&mut self, log_period: Option<Duration>) -> Profiler {
+        self.profiler.get_or_insert_with(|| Profiler::new(log_period)).clone()
+    }
+
+    pub fn screenshot(&self) -> Screenshot {
+        self.screenshot.clone()
+    }
+
+    pub fn with_screenshot_key(&mut self, key: winit::event::VirtualKeyCode, pattern: &str) -> &mut Self {
+        self.screenshot_key = Some(ScreenshotKey::new(key, pattern));
+        self
+    }
+
     pub fn save_images_as(&mut self, filename: &str) -> &mut Self {
         self.save_image = Some(filename.to_owned());
         self
     }
 
     pub fn run_title(self, title: &str) -> ! {
-        run_wgpu(&WindowConfiguration { title }, self);
+        self.run(&WindowConfiguration::new(title));
+    }
+
+    pub fn run(mut self, window: &WindowConfiguration) -> ! {
+        if let Some(recording) = self.recording.take() {
+            recording.record(self).expect("Frames recorded");
+            std::process::exit(0);
+        }
+        if let Some(poster) = self.poster.take() {
+            poster.render(self).expect("Poster rendered");
+            std::process::exit(0);
+        }
+        if window.transparent && self.device.alpha_modes.is_empty() {
+            self.device.alpha_modes = vec![
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+            ];
+        }
+        run_wgpu(window, self);
     }
 }
 
@@ -63,9 +228,15 @@ impl Default for RenderConfiguration {
 
 #[must_use]
 pub struct RenderPassConfiguration {
+    label: Option<String>,
     pipelines: Vec<PipelineConfiguration>,
     load: wgpu::LoadOp<wgpu::Color>,
     depth: Option<DepthConfiguration>,
+    viewport: Viewport,
+    target: Option<String>,
+    format: Option<wgpu::TextureFormat>,
+    tone_mapping: ToneMapping,
+    compute: Option<ComputeConfiguration>,
 }
 
 impl RenderPassConfiguration {
@@ -77,19 +248,80 @@ impl RenderPassConfiguration {
 
     pub fn new(pipelines: Vec<PipelineConfiguration>) -> Self {
         Self {
+            label: None,
             pipelines,
             load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.05, g: 0.062, b: 0.08, a: 1.0 }),
-            depth: Some(DepthConfiguration { format: wgpu::TextureFormat::Depth24Plus }),
+            depth: Some(DepthConfiguration::new(wgpu::TextureFormat::Depth24Plus)),
+            viewport: Viewport::FULL,
+            target: None,
+            format: None,
+            tone_mapping: ToneMapping::Aces,
+            compute: None,
         }
     }
 
+    // Dispatches a compute shader writing the target feedback as a storage texture
+    pub fn compute(compute: ComputeConfiguration) -> Self {
+        Self {
+            depth: None,
+            compute: Some(compute),
+            ..Self::new(vec![])
+        }
+    }
+
+    pub fn with_label(&mut self, label: &str) -> &mut Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
     pub fn with_load(&mut self, load: wgpu::LoadOp<wgpu::Color>) -> &mut Self {
         self.load = load;
         self
     }
 
     pub fn with_depth(&mut self, format: Option<wgpu::TextureFormat>) -> &mut Self {
-        self.depth = format.map(|frm| DepthConfiguration { format: frm });
+        self.depth = format.map(DepthConfiguration::new);
+        self
+    }
+
+    pub fn with_depth_ops(&mut self, load: wgpu::LoadOp<f32>, store: bool) -> &mut Self {
+        if let Some(depth) = self.depth.as_mut() {
+            depth.load = load;
+            depth.store = store;
+        }
+        self
+    }
+
+    // Depth test and clear value of the projection, a loaded depth is kept. With reverse-Z
+    // the buffer switches to floats, where reverse-Z spreads the precision evenly.
+    pub fn with_depth_projection(&mut self, projection: Projection) -> &mut Self {
+        if let Some(depth) = self.depth.as_mut() {
+            if let wgpu::LoadOp::Clear(_) = depth.load {
+                depth.load = wgpu::LoadOp::Clear(projection.depth_clear());
+            }
+            depth.compare = projection.depth_compare();
+            depth.float = projection.is_reverse_z();
+        }
+        self
+    }
+
+    pub fn with_viewport(&mut self, viewport: Viewport) -> &mut Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn with_target(&mut self, texture: &str) -> &mut Self {
+        self.target = Some(texture.to_owned());
+        self
+    }
+
+    pub fn with_format(&mut self, format: wgpu::TextureFormat) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_tone_mapping(&mut self, tone_mapping: ToneMapping) -> &mut Self {
+        self.tone_mapping = tone_mapping;
         self
     }
 }
@@ -99,6 +331,7 @@ impl RenderPassConfiguration {
 
 #[must_use]
 pub struct PipelineConfiguration {
+    label: Option<String>,
     shader_source: String,
     vertex_count: usize,
     topology: wgpu::PrimitiveTopology,
@@ -109,11 +342,18 @@ pub struct PipelineConfiguration {
     uniforms: UniformsConfiguration,
     listeners: Vec<Box<dyn Content<()>>>,
     textures: Vec<TextureInfo>,
+    inputs: Vec<String>,
+    viewport: Option<Viewport>,
+    depth_test: bool,
+    blend: Option<wgpu::BlendState>,
+    frame_uniform: bool,
+    shared_uniforms: bool,
 }
 
 impl PipelineConfiguration {
     pub fn new(shader_source: &str) -> Self {
         PipelineConfiguration {
+            label: None,
             shader_source: shader_source.to_owned(),
             vertex_count: 0,
             topology: wgpu::PrimitiveTopology::TriangleList,
@@ -124,9 +364,28 @@ impl PipelineConfiguration {
             indices: None,
             uniforms: UniformsConfiguration::default(),
             textures: vec![],
+            inputs: vec![],
+            viewport: None,
+            depth_test: true,
+            blend: None,
+            frame_uniform: false,
+            shared_uniforms: false,
         }
     }
 
+    pub fn fullscreen(fragment_source: &str) -> Self {
+        Self::new(format!("{}\n{}", include_str!("fullscreen.wgsl"), fragment_source).as_str())
+            .with_vertex_count(3)
+            .with_cull_mode(None)
+            .with_depth_test(false)
+            .with_frame_uniform()
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
     pub fn with_shader(mut self, shader_source: &str) -> Self {
         self.shader_source = shader_source.to_owned();
         self
@@ -182,16 +441,47 @@ impl PipelineConfiguration {
         self
     }
 
-    pub fn with_textures<const L: usize>(mut self, textures: [TextureInfo; L]) -> Self {
+    pub fn with_textures<T: IntoIterator<Item = TextureInfo>>(mut self, textures: T) -> Self {
         self.textures = textures.into_iter().collect();
         self
     }
 
+    pub fn with_input(mut self, texture: &str) -> Self {
+        self.inputs.push(texture.to_owned());
+        self
+    }
+
     pub fn with_cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
         self.cull_mode = cull_mode;
         self
     }
 
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    pub fn with_depth_test(mut self, depth_test: bool) -> Self {
+        self.depth_test = depth_test;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: Option<wgpu::BlendState>) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_frame_uniform(mut self) -> Self {
+        self.frame_uniform = true;
+        self
+    }
+
+    // Binds the render-level uniforms at group 2
+    pub fn with_shared_uniforms(mut self) -> Self {
+        self.shared_uniforms = true;
+        self
+    }
+
     pub fn add_listener(&mut self, listener: Box<dyn Content<()>>) -> &mut Self {
         self.listeners.push(listener);
         self
@@ -215,18 +505,112 @@ impl PipelineConfiguration {
     }
 }
 
+//
+// ComputeConfiguration
+
+#[must_use]
+pub struct ComputeConfiguration {
+    label: Option<String>,
+    shader_source: String,
+    workgroup_size: (u32, u32),
+    uniforms: UniformsConfiguration,
+    listeners: Vec<Box<dyn Content<()>>>,
+    textures: Vec<TextureInfo>,
+    inputs: Vec<String>,
+    frame_uniform: bool,
+    shared_uniforms: bool,
+}
+
+impl ComputeConfiguration {
+    // The shader's cs_main is dispatched over the target texels, in workgroups matching its @workgroup_size
+    pub fn new(shader_source: &str) -> Self {
+        Self {
+            label: None,
+            shader_source: shader_source.to_owned(),
+            workgroup_size: (8, 8),
+            uniforms: UniformsConfiguration::default(),
+            listeners: vec![],
+            textures: vec![],
+            inputs: vec![],
+            frame_uniform: false,
+            shared_uniforms: false,
+        }
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    pub fn with_workgroup_size(mut self, width: u32, height: u32) -> Self {
+        self.workgroup_size = (width, height);
+        self
+    }
+
+    pub fn with_textures<T: IntoIterator<Item = TextureInfo>>(mut self, textures: T) -> Self {
+        self.textures = textures.into_iter().collect();
+        self
+    }
+
+    pub fn with_input(mut self, texture: &str) -> Self {
+        self.inputs.push(texture.to_owned());
+        self
+    }
+
+    pub fn with_frame_uniform(mut self) -> Self {
+        self.frame_uniform = true;
+        self
+    }
+
+    pub fn with_shared_uniforms(mut self) -> Self {
+        self.shared_uniforms = true;
+        self
+    }
+
+    pub fn add_listener(&mut self, listener: Box<dyn Content<()>>) -> &mut Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    pub fn uniforms(&mut self) -> &mut UniformsConfiguration {
+        &mut self.uniforms
+    }
+}
+
 //
 // DepthConfiguration
 
 struct DepthConfiguration {
     format: wgpu::TextureFormat,
+    load: wgpu::LoadOp<f32>,
+    store: bool,
+    compare: wgpu::CompareFunction,
+    // Overrides the format with floats for reverse-Z
+    float: bool,
+}
+
+impl DepthConfiguration {
+    fn new(format: wgpu::TextureFormat) -> Self {
+        Self {
+            format,
+            load: wgpu::LoadOp::Clear(1.0),
+            store: true,
+            compare: wgpu::CompareFunction::LessEqual,
+            float: false,
+        }
+    }
+
+    fn format(&self) -> wgpu::TextureFormat {
+        if self.float { wgpu::TextureFormat::Depth32Float } else { self.format }
+    }
 }
 
 pub type Configurator<T> = FuncBox<T, T>;
 
 
-pub fn run_wgpu(window_config: &WindowConfiguration, render_config: RenderConfiguration) -> ! {
-    window::show(window_config, move |window| {
+pub fn run_wgpu(window_config: &WindowConfiguration, mut render_config: RenderConfiguration) -> ! {
+    let clock = render_config.clock.take().unwrap_or_default();
+    window::show(window_config, clock, move |window| {
         webgpu::WebGPURender::content(window, render_config).expect("Valid configuration")
     })
 }
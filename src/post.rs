@@ -0,0 +1,247 @@
+use crate::{AttachmentConfiguration, PipelineConfiguration, RenderConfiguration, RenderPassConfiguration};
+
+//
+// ToneMapping
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToneMapping {
+    Clamp,
+    Reinhard,
+    Aces,
+}
+
+impl ToneMapping {
+    fn id(self) -> u32 {
+        match self {
+            ToneMapping::Clamp => 0,
+            ToneMapping::Reinhard => 1,
+            ToneMapping::Aces => 2,
+        }
+    }
+}
+
+//
+// PostProcessing
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct PostProcessing {
+    format: wgpu::TextureFormat,
+    exposure: f32,
+    tone_mapping: ToneMapping,
+    bloom: Option<(f32, f32)>,
+    fxaa: bool,
+    vignette: f32,
+    gamma: Option<f32>,
+}
+
+impl PostProcessing {
+    const SCENE: &'static str = "post_scene";
+    const BRIGHT: &'static str = "post_bright";
+    const BLUR: &'static str = "post_blur";
+    const BLOOM: &'static str = "post_bloom";
+    const LDR: &'static str = "post_ldr";
+
+    pub fn new() -> Self {
+        Self {
+            format: wgpu::TextureFormat::Rgba16Float,
+            exposure: 1.0,
+            tone_mapping: ToneMapping::Aces,
+            bloom: None,
+            fxaa: false,
+            vignette: 0.0,
+            gamma: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_exposure(mut self, exposure: f32) -> Self {
+        self.exposure = exposure;
+        self
+    }
+
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMapping) -> Self {
+        self.tone_mapping = tone_mapping;
+        self
+    }
+
+    pub fn with_bloom(mut self, threshold: f32, intensity: f32) -> Self {
+        self.bloom = Some((threshold, intensity));
+        self
+    }
+
+    pub fn with_fxaa(mut self, fxaa: bool) -> Self {
+        self.fxaa = fxaa;
+        self
+    }
+
+    pub fn with_vignette(mut self, vignette: f32) -> Self {
+        self.vignette = vignette;
+        self
+    }
+
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    // Redirects passes drawing to the surface into an offscreen scene target and appends
+    // the effect passes presenting it.
+    pub(crate) fn apply(self, conf: &mut RenderConfiguration, surface: wgpu::TextureFormat) {
+        for pass in conf.render_passes.iter_mut().filter(|pass| pass.target.is_none()) {
+            pass.with_target(Self::SCENE);
+        }
+        conf.add_attachment(AttachmentConfiguration::new(Self::SCENE).with_format(self.format));
+
+        let bloom = match self.bloom {
+            Some((threshold, _)) => {
+                self.add_bloom(conf, threshold);
+                format!("textureSample({0}_texture, {0}_sampler, uv).rgb", Self::BLOOM)
+            }
+            None => "vec3<f32>(0.0)".to_owned(),
+        };
+        let mut composite = self.composite(Self::SCENE, &bloom, !self.fxaa && surface.is_srgb());
+        if self.bloom.is_some() {
+            composite = composite.with_input(Self::BLOOM);
+        }
+        let composite_pass = Self::pass(conf, composite, "Tone mapping");
+
+        if self.fxaa {
+            composite_pass.with_target(Self::LDR);
+            conf.add_attachment(AttachmentConfiguration::new(Self::LDR).with_format(wgpu::TextureFormat::Rgba8Unorm));
+            let fxaa = PipelineConfiguration::fullscreen(&self.shader(include_str!("post/fxaa.wgsl"), surface.is_srgb()))
+                .with_input(Self::LDR)
+                .with_label("FXAA");
+            Self::pass(conf, fxaa, "Anti-aliasing");
+        }
+    }
+
+    // Runs of consecutive passes drawing to the surface in their own format render into an offscreen
+    // target, resolved onto the surface right after the run. The resolve blends by alpha over the
+    // passes drawn before, so a run cleared to transparent or starting with a load overlays them.
+    // Passes with an explicit target, including those redirected by post-processing, are skipped.
+    pub(crate) fn resolve_formats(conf: &mut RenderConfiguration, surface: wgpu::TextureFormat) {
+        let mut resolves: Vec<Resolve> = vec![];
+        let mut in_run = false;
+        let mut drawn = false;
+        for (index, pass) in conf.render_passes.iter_mut().enumerate().filter(|(_, pass)| pass.target.is_none()) {
+            let Some(format) = pass.format.filter(|&format| format != surface) else {
+                in_run = false;
+                drawn = true;
+                continue;
+            };
+            match resolves.last_mut().filter(|resolve| in_run && resolve.format == format) {
+                Some(resolve) => {
+                    resolve.last = index;
+                    resolve.tone_mapping = pass.tone_mapping;
+                }
+                None => {
+                    // The offscreen target still holds the previous frame
+                    if pass.load == wgpu::LoadOp::Load {
+                        pass.load = wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT);
+                    }
+                    let name = format!("resolve_{}", resolves.len());
+                    resolves.push(Resolve { format, name, last: index, tone_mapping: pass.tone_mapping, over: drawn });
+                }
+            }
+            if let Some(resolve) = resolves.last() {
+                pass.with_target(&resolve.name);
+            }
+            in_run = true;
+            drawn = true;
+        }
+
+        resolves.sort_by_key(|resolve| core::cmp::Reverse(resolve.last));
+        for Resolve { format, name, last, tone_mapping, over } in resolves {
+            conf.add_attachment(AttachmentConfiguration::new(&name).with_format(format));
+            let resolve = Self::new()
+                .with_tone_mapping(tone_mapping)
+                .composite(&name, "vec3<f32>(0.0)", surface.is_srgb())
+                .with_blend(Some(wgpu::BlendState::ALPHA_BLENDING))
+                .with_label("Resolve");
+            let mut pass = RenderPassConfiguration::new(vec![resolve]);
+            pass.with_label(&format!("Resolve {format:?}")).with_depth(None);
+            if over {
+                pass.with_load(wgpu::LoadOp::Load);
+            }
+            conf.render_passes.insert(last + 1, pass);
+        }
+    }
+
+    fn composite(&self, source: &str, bloom: &str, srgb_target: bool) -> PipelineConfiguration {
+        let composite = format!(
+            "fn bloom(uv: vec2<f32>) -> vec3<f32> {{ return {bloom}; }}\n{}",
+            include_str!("post/composite.wgsl").replace("SCENE", source),
+        );
+        PipelineConfiguration::fullscreen(&self.shader(&composite, srgb_target))
+            .with_input(source)
+            .with_label("Composite")
+    }
+
+    fn add_bloom(&self, conf: &mut RenderConfiguration, threshold: f32) {
+        let half = |name| AttachmentConfiguration::new(name).with_format(self.format).with_scale(0.5);
+        conf.add_attachment(half(Self::BRIGHT));
+        conf.add_attachment(half(Self::BLUR));
+        conf.add_attachment(half(Self::BLOOM));
+
+        let bright = format!("const BLOOM_THRESHOLD: f32 = {threshold:?};\n{}", include_str!("post/bright.wgsl"));
+        let bright = PipelineConfiguration::fullscreen(&bright).with_input(Self::SCENE).with_label("Bright");
+        Self::pass(conf, bright, "Bloom threshold").with_target(Self::BRIGHT);
+
+        for (source, target, direction) in [(Self::BRIGHT, Self::BLUR, "1.0, 0.0"), (Self::BLUR, Self::BLOOM, "0.0, 1.0")] {
+            let blur = format!(
+                "const BLUR_DIRECTION: vec2<f32> = vec2<f32>({direction});\nconst BLUR_SPREAD: f32 = 1.5;\n{}",
+                include_str!("post/blur.wgsl").replace("BLUR_SOURCE", source),
+            );
+            let blur = PipelineConfiguration::fullscreen(&blur).with_input(source).with_label("Blur");
+            Self::pass(conf, blur, "Bloom blur").with_target(target);
+        }
+    }
+
+    fn pass<'a>(conf: &'a mut RenderConfiguration, pipeline: PipelineConfiguration, label: &str) -> &'a mut RenderPassConfiguration {
+        conf.new_pass(vec![pipeline])
+            .with_label(label)
+            .with_depth(None)
+    }
+
+    fn shader(&self, source: &str, srgb_target: bool) -> String {
+        let (_, intensity) = self.bloom.unwrap_or_default();
+        let encode = match self.gamma {
+            Some(gamma) => format!("pow(max(color, vec3<f32>(0.0)), vec3<f32>({:?}))", 1.0 / gamma),
+            None => "linear_to_srgb(color)".to_owned(),
+        };
+        let present = if srgb_target { "srgb_to_linear(color)" } else { "color" };
+        format!(
+            "const EXPOSURE: f32 = {:?};\n\
+             const TONE_MAPPING: u32 = {}u;\n\
+             const BLOOM_INTENSITY: f32 = {intensity:?};\n\
+             const VIGNETTE: f32 = {:?};\n\
+             {}\n\
+             fn encode(color: vec3<f32>) -> vec3<f32> {{ return {encode}; }}\n\
+             fn present(color: vec3<f32>) -> vec3<f32> {{ return {present}; }}\n\
+             {source}",
+            self.exposure,
+            self.tone_mapping.id(),
+            self.vignette,
+            include_str!("post/color.wgsl"),
+        )
+    }
+}
+
+struct Resolve {
+    format: wgpu::TextureFormat,
+    name: String,
+    last: usize,
+    tone_mapping: ToneMapping,
+    over: bool,
+}
+
+impl Default for PostProcessing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
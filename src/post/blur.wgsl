@@ -0,0 +1,13 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let texel = BLUR_DIRECTION / vec2<f32>(textureDimensions(BLUR_SOURCE_texture));
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+    var color = textureSample(BLUR_SOURCE_texture, BLUR_SOURCE_sampler, in.target_uv).rgb * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = texel * f32(i) * BLUR_SPREAD;
+        color += textureSample(BLUR_SOURCE_texture, BLUR_SOURCE_sampler, in.target_uv + offset).rgb * weights[i];
+        color += textureSample(BLUR_SOURCE_texture, BLUR_SOURCE_sampler, in.target_uv - offset).rgb * weights[i];
+    }
+    return vec4<f32>(color, 1.0);
+}
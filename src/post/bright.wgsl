@@ -0,0 +1,9 @@
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(post_scene_texture, post_scene_sampler, in.target_uv).rgb;
+    let brightness = max(color.r, max(color.g, color.b));
+    let knee = BLOOM_THRESHOLD * 0.5;
+    let soft = clamp(brightness - BLOOM_THRESHOLD + knee, 0.0, 2.0 * knee);
+    let contribution = max(soft * soft / (4.0 * knee + 1e-4), brightness - BLOOM_THRESHOLD);
+    return vec4<f32>(color * max(contribution, 0.0) / max(brightness, 1e-4), 1.0);
+}
@@ -0,0 +1,30 @@
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    switch TONE_MAPPING {
+        case 1u: {
+            return color / (1.0 + color);
+        }
+        case 2u: {
+            let a = 2.51;
+            let b = 0.03;
+            let c = 2.43;
+            let d = 0.59;
+            let e = 0.14;
+            return clamp((color * (a * color + b)) / (color * (c * color + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+        default: {
+            return clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+    }
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(SCENE_texture, SCENE_sampler, in.target_uv);
+    var color = (scene.rgb + bloom(in.target_uv) * BLOOM_INTENSITY) * EXPOSURE;
+    color = tone_map(color);
+
+    let edge = in.uv * (1.0 - in.uv);
+    color *= mix(1.0, clamp(pow(edge.x * edge.y * 16.0, 0.25), 0.0, 1.0), VIGNETTE);
+
+    return vec4<f32>(present(encode(color)), scene.a);
+}
@@ -0,0 +1,40 @@
+const FXAA_SPAN_MAX: f32 = 8.0;
+const FXAA_REDUCE_MUL: f32 = 0.125;
+const FXAA_REDUCE_MIN: f32 = 0.0078125;
+
+fn fxaa_sample(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(post_ldr_texture, post_ldr_sampler, uv, 0.0).rgb;
+}
+
+fn luma(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(post_ldr_texture));
+    let uv = in.target_uv;
+
+    let luma_nw = luma(fxaa_sample(uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(fxaa_sample(uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(fxaa_sample(uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(fxaa_sample(uv + vec2<f32>(1.0, 1.0) * texel));
+    let center = fxaa_sample(uv);
+    let luma_m = luma(center);
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    var dir = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * FXAA_REDUCE_MUL, FXAA_REDUCE_MIN);
+    let scale = 1.0 / (min(abs(dir.x), abs(dir.y)) + reduce);
+    dir = clamp(dir * scale, vec2<f32>(-FXAA_SPAN_MAX), vec2<f32>(FXAA_SPAN_MAX)) * texel;
+
+    let near = 0.5 * (fxaa_sample(uv + dir * (1.0 / 3.0 - 0.5)) + fxaa_sample(uv + dir * (2.0 / 3.0 - 0.5)));
+    let far = near * 0.5 + 0.25 * (fxaa_sample(uv - dir * 0.5) + fxaa_sample(uv + dir * 0.5));
+    let luma_far = luma(far);
+    let color = select(far, near, luma_far < luma_min || luma_far > luma_max);
+    return vec4<f32>(present(color), 1.0);
+}
@@ -0,0 +1,143 @@
+use core::time::Duration;
+
+use anyhow::{Context, Result};
+use cgmath::Matrix4;
+use image::{DynamicImage, imageops};
+use image::imageops::FilterType;
+
+use crate::{Content, InputState, RenderConfiguration, Viewport};
+use crate::screenshot::save_image;
+use crate::webgpu::WebGPURender;
+
+//
+// Tile
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct Tile {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Tile {
+    pub const FULL: Tile = Tile { x: 0.0, y: 0.0, width: 1.0, height: 1.0 };
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    #[must_use]
+    pub fn matrix(&self) -> Matrix4<f32> {
+        let center_x = 2.0 * self.x + self.width - 1.0;
+        let center_y = 1.0 - 2.0 * self.y - self.height;
+        Matrix4::from_translation([-center_x / self.width, -center_y / self.height, 0.0].into())
+            * Matrix4::from_nonuniform_scale(1.0 / self.width, 1.0 / self.height, 1.0)
+    }
+
+    pub(crate) fn clip(&self, viewport: &Viewport) -> Option<(Viewport, Tile)> {
+        let left = self.x.max(viewport.x);
+        let top = self.y.max(viewport.y);
+        let right = (self.x + self.width).min(viewport.x + viewport.width);
+        let bottom = (self.y + self.height).min(viewport.y + viewport.height);
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        let target = Viewport::new(
+            (left - self.x) / self.width,
+            (top - self.y) / self.height,
+            (right - left) / self.width,
+            (bottom - top) / self.height,
+        ).with_scissor(viewport.scissor);
+        let local = Tile::new(
+            (left - viewport.x) / viewport.width,
+            (top - viewport.y) / viewport.height,
+            (right - left) / viewport.width,
+            (bottom - top) / viewport.height,
+        );
+        Some((target, local))
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+//
+// Poster
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Poster {
+    pub filename: String,
+    pub width: u32,
+    pub height: u32,
+    pub supersampling: u32,
+    pub tile_size: u32,
+}
+
+impl Poster {
+    pub fn new(filename: &str, width: u32, height: u32) -> Self {
+        Self {
+            filename: filename.to_owned(),
+            width,
+            height,
+            supersampling: 1,
+            tile_size: 2048,
+        }
+    }
+
+    pub fn with_supersampling(mut self, supersampling: u32) -> Self {
+        self.supersampling = supersampling.max(1);
+        self
+    }
+
+    pub fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size.max(1);
+        self
+    }
+
+    pub(crate) fn render(self, mut conf: RenderConfiguration) -> Result<()> {
+        let samples = self.supersampling;
+        let mut clock = conf.clock.take().unwrap_or_default();
+        let mut render = pollster::block_on(WebGPURender::new(None, conf))?;
+        render.resize((), self.width * samples, self.height * samples);
+
+        let input = InputState::default();
+        for time in clock.advance(Duration::ZERO) {
+            render.update((), &time, &input);
+        }
+
+        let tile_size = self.tile_size.min(render.max_texture_size() / samples).max(1);
+        let mut poster: Option<DynamicImage> = None;
+        for y in (0..self.height).step_by(tile_size as usize) {
+            for x in (0..self.width).step_by(tile_size as usize) {
+                let width = tile_size.min(self.width - x);
+                let height = tile_size.min(self.height - y);
+                let tile = Tile::new(
+                    x as f32 / self.width as f32,
+                    y as f32 / self.height as f32,
+                    width as f32 / self.width as f32,
+                    height as f32 / self.height as f32,
+                );
+
+                let mut image = render.capture_tile(&tile, width * samples, height * samples)
+                    .context(format!("Render tile at {x}x{y}"))?;
+                if samples > 1 {
+                    image = image.resize_exact(width, height, FilterType::Lanczos3);
+                }
+                let poster = poster.get_or_insert_with(|| DynamicImage::new(self.width, self.height, image.color()));
+                imageops::replace(poster, &image, x.into(), y.into());
+            }
+        }
+
+        match poster {
+            Some(poster) => save_image(&poster, &self.filename),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use core::cell::RefCell;
+use core::fmt;
+use core::time::Duration;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+//
+// ProfileReport
+
+#[derive(Clone, Debug, Default)]
+pub struct ProfileReport {
+    pub frame: u64,
+    pub gpu: bool,
+    pub total: Duration,
+    pub passes: Vec<PassTiming>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PassTiming {
+    pub label: String,
+    pub duration: Duration,
+    pub pipelines: Vec<PipelineTiming>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PipelineTiming {
+    pub label: String,
+    pub duration: Duration,
+}
+
+impl ProfileReport {
+    fn same_shape(&self, other: &ProfileReport) -> bool {
+        self.gpu == other.gpu
+            && self.passes.len() == other.passes.len()
+            && self.passes.iter().zip(&other.passes)
+                .all(|(a, b)| a.label == b.label && a.pipelines.len() == b.pipelines.len())
+    }
+
+    fn accumulate(&mut self, other: &ProfileReport) {
+        self.frame = other.frame;
+        self.total += other.total;
+        for (pass, other) in self.passes.iter_mut().zip(&other.passes) {
+            pass.duration += other.duration;
+            for (pipeline, other) in pass.pipelines.iter_mut().zip(&other.pipelines) {
+                pipeline.duration += other.duration;
+            }
+        }
+    }
+
+    fn average(mut self, frames: u32) -> ProfileReport {
+        let frames = frames.max(1);
+        self.total /= frames;
+        for pass in &mut self.passes {
+            pass.duration /= frames;
+            for pipeline in &mut pass.pipelines {
+                pipeline.duration /= frames;
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let source = if self.gpu { "GPU" } else { "CPU" };
+        writeln!(f, "{source} frame {}: {:.3} ms", self.frame, millis(self.total))?;
+        for pass in &self.passes {
+            writeln!(f, "  {}: {:.3} ms", pass.label, millis(pass.duration))?;
+            for pipeline in &pass.pipelines {
+                writeln!(f, "    {}: {:.3} ms", pipeline.label, millis(pipeline.duration))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+//
+// Profiler
+
+#[derive(Clone)]
+pub struct Profiler {
+    state: Rc<RefCell<ProfilerState>>,
+}
+
+struct ProfilerState {
+    latest: Option<ProfileReport>,
+    log_period: Option<Duration>,
+    log_start: Instant,
+    accumulated: Option<(ProfileReport, u32)>,
+}
+
+impl Profiler {
+    pub(crate) fn new(log_period: Option<Duration>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ProfilerState {
+                latest: None,
+                log_period,
+                log_start: Instant::now(),
+                accumulated: None,
+            })),
+        }
+    }
+
+    #[must_use]
+    pub fn report(&self) -> Option<ProfileReport> {
+        self.state.borrow().latest.clone()
+    }
+
+    fn publish(&self, report: ProfileReport) {
+        let mut state = self.state.borrow_mut();
+        if let Some(period) = state.log_period {
+            match state.accumulated.as_mut() {
+                Some((accumulated, frames)) if accumulated.same_shape(&report) => {
+                    accumulated.accumulate(&report);
+                    *frames += 1;
+                }
+                _ => state.accumulated = Some((report.clone(), 1)),
+            }
+            if state.log_start.elapsed() >= period {
+                if let Some((accumulated, frames)) = state.accumulated.take() {
+                    print!("Average over {frames} frames, {}", accumulated.average(frames));
+                }
+                state.log_start = Instant::now();
+            }
+        }
+        state.latest = Some(report);
+    }
+}
+
+//
+// FrameProfiler
+
+pub(crate) struct FrameProfiler {
+    handle: Profiler,
+    labels: Vec<(String, Vec<String>)>,
+    timestamps: Option<Timestamps>,
+    frame: u64,
+    active: bool,
+    scopes: Vec<Scope>,
+    open: Vec<usize>,
+    pending: Vec<PendingProfile>,
+}
+
+struct Scope {
+    pass: usize,
+    pipeline: Option<usize>,
+    query: u32,
+    start: Instant,
+    end: Instant,
+}
+
+struct Timestamps {
+    query_set: wgpu::QuerySet,
+    resolve: wgpu::Buffer,
+    buffers: Vec<(wgpu::Buffer, bool)>,
+    current: Option<usize>,
+    inside_passes: bool,
+    period: f32,
+}
+
+struct PendingProfile {
+    frame: u64,
+    buffer: usize,
+    scopes: Vec<Scope>,
+    mapped: Option<Arc<AtomicBool>>,
+}
+
+impl FrameProfiler {
+    const IN_FLIGHT: usize = 3;
+    const TIMESTAMP_SIZE: u64 = 8;
+
+    pub(crate) fn features() -> wgpu::Features {
+        wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES
+    }
+
+    pub(crate) fn new(
+        handle: Profiler,
+        labels: Vec<(String, Vec<String>)>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Self {
+        let features = device.features();
+        let timestamps = features.contains(wgpu::Features::TIMESTAMP_QUERY).then(|| {
+            let queries = labels.iter().map(|(_, pipelines)| 2 + 2 * pipelines.len()).sum::<usize>();
+            let count = u32::try_from(queries.max(2)).expect("Query count should fit into u32");
+            let size = u64::from(count) * Self::TIMESTAMP_SIZE;
+            let buffer = |label: &str, usage: wgpu::BufferUsages| device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage,
+                mapped_at_creation: false,
+            });
+            Timestamps {
+                query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                    label: Some("Timestamps"),
+                    ty: wgpu::QueryType::Timestamp,
+                    count,
+                }),
+                resolve: buffer("Timestamps resolve", wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC),
+                buffers: (0..Self::IN_FLIGHT)
+                    .map(|_| (buffer("Timestamps read", wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ), false))
+                    .collect(),
+                current: None,
+                inside_passes: features.contains(wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES),
+                period: queue.get_timestamp_period(),
+            }
+        });
+        Self { handle, labels, timestamps, frame: 0, active: false, scopes: vec![], open: vec![], pending: vec![] }
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.scopes.clear();
+        self.open.clear();
+        self.active = match self.timestamps.as_mut() {
+            None => true,
+            Some(timestamps) => {
+                timestamps.current = timestamps.buffers.iter().position(|&(_, busy)| !busy);
+                timestamps.current.is_some()
+            }
+        };
+    }
+
+    pub(crate) fn begin_pass(&mut self, encoder: &mut wgpu::CommandEncoder, pass: usize) {
+        if let Some(query) = self.begin(pass, None) {
+            if let Some(timestamps) = self.timestamps.as_ref() {
+                encoder.write_timestamp(&timestamps.query_set, query);
+            }
+        }
+    }
+
+    pub(crate) fn end_pass(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(query) = self.end() {
+            if let Some(timestamps) = self.timestamps.as_ref() {
+                encoder.write_timestamp(&timestamps.query_set, query + 1);
+            }
+        }
+    }
+
+    pub(crate) fn begin_pipeline(&mut self, render_pass: &mut wgpu::RenderPass, pass: usize, pipeline: usize) {
+        if let Some((query_set, query)) = self.begin_inside(pass, pipeline) {
+            render_pass.write_timestamp(query_set, query);
+        }
+    }
+
+    pub(crate) fn end_pipeline(&mut self, render_pass: &mut wgpu::RenderPass) {
+        if let Some((query_set, query)) = self.end_inside() {
+            render_pass.write_timestamp(query_set, query);
+        }
+    }
+
+    pub(crate) fn begin_compute(&mut self, compute_pass: &mut wgpu::ComputePass, pass: usize, pipeline: usize) {
+        if let Some((query_set, query)) = self.begin_inside(pass, pipeline) {
+            compute_pass.write_timestamp(query_set, query);
+        }
+    }
+
+    pub(crate) fn end_compute(&mut self, compute_pass: &mut wgpu::ComputePass) {
+        if let Some((query_set, query)) = self.end_inside() {
+            compute_pass.write_timestamp(query_set, query);
+        }
+    }
+
+    // Timestamps inside passes are skipped when the device does not support them
+    fn begin_inside(&mut self, pass: usize, pipeline: usize) -> Option<(&wgpu::QuerySet, u32)> {
+        if self.timestamps.as_ref().is_some_and(|timestamps| !timestamps.inside_passes) {
+            return None;
+        }
+        let query = self.begin(pass, Some(pipeline))?;
+        self.timestamps.as_ref().map(|timestamps| (&timestamps.query_set, query))
+    }
+
+    fn end_inside(&mut self) -> Option<(&wgpu::QuerySet, u32)> {
+        if self.timestamps.as_ref().is_some_and(|timestamps| !timestamps.inside_passes) {
+            return None;
+        }
+        let query = self.end()?;
+        self.timestamps.as_ref().map(|timestamps| (&timestamps.query_set, query + 1))
+    }
+
+    fn begin(&mut self, pass: usize, pipeline: Option<usize>) -> Option<u32> {
+        if !self.active {
+            return None;
+        }
+        let query = 2 * u32::try_from(self.scopes.len()).expect("Scope count should fit into u32");
+        let now = Instant::now();
+        self.open.push(self.scopes.len());
+        self.scopes.push(Scope { pass, pipeline, query, start: now, end: now });
+        Some(query)
+    }
+
+    fn end(&mut self) -> Option<u32> {
+        if !self.active {
+            return None;
+        }
+        let index = self.open.pop().expect("Profile scope opened");
+        let scope = self.scopes.get_mut(index).expect("Valid profile scope");
+        scope.end = Instant::now();
+        Some(scope.query)
+    }
+
+    pub(crate) fn end_frame(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if !self.active {
+            return;
+        }
+        self.active = false;
+        self.frame += 1;
+        let scopes = core::mem::take(&mut self.scopes);
+
+        let Some(timestamps) = self.timestamps.as_mut() else {
+            let report = self.report(self.frame, false, &scopes, |scope| scope.end - scope.start);
+            self.handle.publish(report);
+            return;
+        };
+        let Some(buffer) = timestamps.current.take() else {
+            return;
+        };
+        let queries = 2 * u32::try_from(scopes.len()).expect("Scope count should fit into u32");
+        if queries > 0 {
+            let (read, busy) = timestamps.buffers.get_mut(buffer).expect("Valid timestamp buffer");
+            encoder.resolve_query_set(&timestamps.query_set, 0..queries, &timestamps.resolve, 0);
+            encoder.copy_buffer_to_buffer(&timestamps.resolve, 0, read, 0, u64::from(queries) * Self::TIMESTAMP_SIZE);
+            *busy = true;
+            self.pending.push(PendingProfile { frame: self.frame, buffer, scopes, mapped: None });
+        }
+    }
+
+    pub(crate) fn submitted(&mut self) {
+        let Some(timestamps) = self.timestamps.as_ref() else {
+            return;
+        };
+        for pending in self.pending.iter_mut().filter(|pending| pending.mapped.is_none()) {
+            let mapped = Arc::new(AtomicBool::new(false));
+            let flag = mapped.clone();
+            let (buffer, _) = timestamps.buffers.get(pending.buffer).expect("Valid timestamp buffer");
+            buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                result.expect("Timestamp buffer mapped");
+                flag.store(true, Ordering::Release);
+            });
+            pending.mapped = Some(mapped);
+        }
+    }
+
+    pub(crate) fn poll(&mut self, device: &wgpu::Device) {
+        if self.pending.is_empty() {
+            return;
+        }
+        device.poll(wgpu::Maintain::Poll);
+
+        let (ready, pending): (Vec<_>, Vec<_>) = self.pending.drain(..)
+            .partition(|pending| pending.mapped.as_ref().is_some_and(|mapped| mapped.load(Ordering::Acquire)));
+        self.pending = pending;
+        for profile in ready {
+            let Some(timestamps) = self.timestamps.as_mut() else {
+                return;
+            };
+            let (buffer, busy) = timestamps.buffers.get_mut(profile.buffer).expect("Valid timestamp buffer");
+            let values: Vec<u64> = buffer.slice(..).get_mapped_range()
+                .chunks_exact(Self::TIMESTAMP_SIZE as usize)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+                .collect();
+            buffer.unmap();
+            *busy = false;
+
+            let period = f64::from(timestamps.period);
+            let report = self.report(profile.frame, true, &profile.scopes, |scope| {
+                let index = scope.query as usize;
+                match (values.get(index), values.get(index + 1)) {
+                    (Some(&start), Some(&end)) =>
+                        Duration::from_nanos((end.saturating_sub(start) as f64 * period) as u64),
+                    _ => Duration::ZERO,
+                }
+            });
+            self.handle.publish(report);
+        }
+    }
+
+    fn report<F: Fn(&Scope) -> Duration>(&self, frame: u64, gpu: bool, scopes: &[Scope], duration: F) -> ProfileReport {
+        let mut passes: Vec<PassTiming> = vec![];
+        for scope in scopes {
+            let Some((pass_label, pipeline_labels)) = self.labels.get(scope.pass) else {
+                continue;
+            };
+            match scope.pipeline {
+                None => passes.push(PassTiming {
+                    label: pass_label.clone(),
+                    duration: duration(scope),
+                    pipelines: vec![],
+                }),
+                Some(pipeline) => if let Some(pass) = passes.last_mut() {
+                    pass.pipelines.push(PipelineTiming {
+                        label: pipeline_labels.get(pipeline).cloned().unwrap_or_default(),
+                        duration: duration(scope),
+                    });
+                },
+            }
+        }
+        ProfileReport {
+            frame,
+            gpu,
+            total: passes.iter().map(|pass| pass.duration).sum(),
+            passes,
+        }
+    }
+}
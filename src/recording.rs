@@ -0,0 +1,93 @@
+use core::time::Duration;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use image::{Delay, Frame};
+use image::codecs::gif::{GifEncoder, Repeat};
+
+use crate::{Content, InputState, RenderConfiguration};
+use crate::screenshot::save_image;
+use crate::webgpu::WebGPURender;
+
+//
+// Recording
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Recording {
+    pub directory: String,
+    pub frames: usize,
+    pub step: Duration,
+    pub width: u32,
+    pub height: u32,
+    pub gif: bool,
+}
+
+impl Recording {
+    pub fn new(directory: &str, frames: usize) -> Self {
+        Self {
+            directory: directory.to_owned(),
+            frames,
+            step: Duration::from_nanos(1_000_000_000 / 30),
+            width: 1280,
+            height: 720,
+            gif: false,
+        }
+    }
+
+    pub fn with_step(mut self, step: Duration) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_fps(self, fps: u32) -> Self {
+        self.with_step(Duration::from_secs(1) / fps.max(1))
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_gif(mut self, gif: bool) -> Self {
+        self.gif = gif;
+        self
+    }
+
+    pub(crate) fn record(self, mut conf: RenderConfiguration) -> Result<()> {
+        let directory = PathBuf::from(&self.directory);
+        fs::create_dir_all(&directory)
+            .context(format!("Create recording directory '{}'", self.directory))?;
+
+        let mut clock = conf.clock.take().unwrap_or_default();
+        let mut render = pollster::block_on(WebGPURender::new(None, conf))?;
+        render.resize((), self.width, self.height);
+
+        let input = InputState::default();
+        let mut gif_frames = vec![];
+        for index in 0..self.frames {
+            let elapsed = if index == 0 { Duration::ZERO } else { self.step };
+            for time in clock.advance(elapsed) {
+                render.update((), &time, &input);
+            }
+            let image = render.capture_frame()?;
+            let file = directory.join(format!("frame_{:05}.png", index + 1));
+            save_image(&image, &file.to_string_lossy())?;
+            if self.gif {
+                gif_frames.push(Frame::from_parts(image.to_rgba8(), 0, 0, Delay::from_saturating_duration(self.step)));
+            }
+        }
+
+        if self.gif {
+            let file = directory.join("animation.gif");
+            let mut encoder = GifEncoder::new(
+                File::create(&file).context(format!("Create {}", file.display()))?
+            );
+            encoder.set_repeat(Repeat::Infinite)?;
+            encoder.encode_frames(gif_frames)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use core::cell::RefCell;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+use image::{DynamicImage, ImageBuffer, Rgb, Rgba};
+use image::codecs::hdr::HdrEncoder;
+use winit::event::VirtualKeyCode;
+
+use crate::InputEvent;
+
+//
+// Screenshot
+
+#[derive(Clone, Default)]
+pub struct Screenshot {
+    requests: Rc<RefCell<Vec<String>>>,
+}
+
+impl Screenshot {
+    pub fn take(&self, filename: &str) {
+        self.requests.borrow_mut().push(filename.to_owned());
+    }
+
+    fn drain(&self) -> Vec<String> {
+        self.requests.borrow_mut().drain(..).collect()
+    }
+}
+
+//
+// ScreenshotKey
+
+#[derive(Clone, Debug)]
+pub(crate) struct ScreenshotKey {
+    key: VirtualKeyCode,
+    pattern: String,
+    counter: usize,
+}
+
+impl ScreenshotKey {
+    pub(crate) fn new(key: VirtualKeyCode, pattern: &str) -> Self {
+        Self { key, pattern: pattern.to_owned(), counter: 0 }
+    }
+
+    fn filename(&mut self) -> String {
+        self.counter += 1;
+        self.pattern.replace("{}", &format!("{:04}", self.counter))
+    }
+}
+
+//
+// Screenshots
+
+pub(crate) struct Screenshots {
+    handle: Screenshot,
+    key: Option<ScreenshotKey>,
+    pending: Vec<PendingScreenshot>,
+}
+
+impl Screenshots {
+    pub(crate) fn new(handle: Screenshot, key: Option<ScreenshotKey>) -> Self {
+        Self { handle, key, pending: vec![] }
+    }
+
+    pub(crate) fn input(&mut self, event: &InputEvent) {
+        if let Some(key) = self.key.as_mut() {
+            if event.is_pressed(key.key) {
+                self.handle.take(&key.filename());
+            }
+        }
+    }
+
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.handle.drain()
+    }
+
+    pub(crate) fn is_pending(&self, filename: &str) -> bool {
+        self.pending.iter().any(|pending| pending.filename == filename)
+    }
+
+    pub(crate) fn copy(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        filenames: Vec<String>,
+    ) {
+        let readback = Readback::new(texture.width(), texture.height(), texture.format());
+        for filename in filenames {
+            let buffer = readback.buffer(device);
+            readback.copy(encoder, texture, &buffer);
+            self.pending.push(PendingScreenshot { filename, readback, buffer, mapped: None });
+        }
+    }
+
+    pub(crate) fn submitted(&mut self) {
+        for pending in self.pending.iter_mut().filter(|pending| pending.mapped.is_none()) {
+            let mapped = MapResult::default();
+            let result_slot = mapped.clone();
+            pending.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                *result_slot.lock().expect("Screenshot map result") = Some(result);
+            });
+            pending.mapped = Some(mapped);
+        }
+    }
+
+    pub(crate) fn poll(&mut self, device: &wgpu::Device) {
+        if self.pending.is_empty() {
+            return;
+        }
+        device.poll(wgpu::Maintain::Poll);
+
+        let (ready, pending): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(PendingScreenshot::is_ready);
+        self.pending = pending;
+        for screenshot in ready {
+            if let Some(Err(error)) = screenshot.result() {
+                eprintln!("Screenshot failed: {error}");
+                continue;
+            }
+            let data = screenshot.buffer.slice(..).get_mapped_range().to_vec();
+            screenshot.buffer.unmap();
+            let PendingScreenshot { filename, readback, .. } = screenshot;
+            thread::spawn(move || {
+                if let Err(error) = readback.image(&data).and_then(|image| save_image(&image, &filename)) {
+                    eprintln!("Screenshot failed: {error:#}");
+                }
+            });
+        }
+    }
+}
+
+type MapResult = Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>;
+
+struct PendingScreenshot {
+    filename: String,
+    readback: Readback,
+    buffer: wgpu::Buffer,
+    mapped: Option<MapResult>,
+}
+
+impl PendingScreenshot {
+    fn result(&self) -> Option<Result<(), wgpu::BufferAsyncError>> {
+        self.mapped.as_ref().and_then(|mapped| mapped.lock().ok().and_then(|result| result.clone()))
+    }
+
+    fn is_ready(&self) -> bool {
+        self.result().is_some()
+    }
+}
+
+//
+// Readback
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Readback {
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    bytes_per_pixel: u32,
+    padded_bytes_per_row: u32,
+}
+
+impl Readback {
+    const ALIGN: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    pub(crate) fn new(width: u32, height: u32, format: wgpu::TextureFormat) -> Self {
+        let bytes_per_pixel = format.block_size(None).expect("Color format");
+        let padded_bytes_per_row = ((width * bytes_per_pixel - 1) / Self::ALIGN + 1) * Self::ALIGN;
+        Self { width, height, format, bytes_per_pixel, padded_bytes_per_row }
+    }
+
+    pub(crate) fn buffer(&self, device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback"),
+            size: wgpu::BufferAddress::from(self.padded_bytes_per_row * self.height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        })
+    }
+
+    pub(crate) fn copy(&self, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture, buffer: &wgpu::Buffer) {
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+        );
+    }
+
+    pub(crate) fn read(&self, device: &wgpu::Device, buffer: &wgpu::Buffer, submission: wgpu::SubmissionIndex) -> Result<DynamicImage> {
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::WaitForSubmissionIndex(submission));
+
+        let image = self.image(&slice.get_mapped_range());
+        buffer.unmap();
+        image
+    }
+
+    pub(crate) fn image(&self, data: &[u8]) -> Result<DynamicImage> {
+        use wgpu::TextureFormat as F;
+
+        Ok(match self.format {
+            F::Rgba8Unorm | F::Rgba8UnormSrgb =>
+                DynamicImage::ImageRgba8(self.convert(data, |p| Rgba([p[0], p[1], p[2], p[3]]))),
+            F::Bgra8Unorm | F::Bgra8UnormSrgb =>
+                DynamicImage::ImageRgba8(self.convert(data, |p| Rgba([p[2], p[1], p[0], p[3]]))),
+            F::Rgb10a2Unorm => DynamicImage::ImageRgba16(self.convert(data, |p| {
+                let bits = u32::from_le_bytes([p[0], p[1], p[2], p[3]]);
+                let channel = |shift: u32| ((bits >> shift & 0x3ff) * 0xffff / 0x3ff) as u16;
+                Rgba([channel(0), channel(10), channel(20), ((bits >> 30) * 0xffff / 3) as u16])
+            })),
+            F::Rgba16Float => DynamicImage::ImageRgba32F(self.convert(data, |p| {
+                let channel = |i: usize| f16_to_f32(u16::from_le_bytes([p[2 * i], p[2 * i + 1]]));
+                Rgba([channel(0), channel(1), channel(2), channel(3)])
+            })),
+            F::Rgba32Float => DynamicImage::ImageRgba32F(self.convert(data, |p| {
+                let channel = |i: usize| f32::from_le_bytes([p[4 * i], p[4 * i + 1], p[4 * i + 2], p[4 * i + 3]]);
+                Rgba([channel(0), channel(1), channel(2), channel(3)])
+            })),
+            format => bail!("Unsupported screenshot format {format:?}"),
+        })
+    }
+
+    #[allow(clippy::indexing_slicing)]
+    fn convert<S, F>(&self, data: &[u8], pixel: F) -> ImageBuffer<Rgba<S>, Vec<S>>
+        where S: image::Primitive, Rgba<S>: image::Pixel<Subpixel = S>, F: Fn(&[u8]) -> Rgba<S>
+    {
+        let row = (self.width * self.bytes_per_pixel) as usize;
+        let pixels = data
+            .chunks(self.padded_bytes_per_row as usize)
+            .take(self.height as usize)
+            .flat_map(|padded| padded[..row].chunks_exact(self.bytes_per_pixel as usize))
+            .flat_map(|bytes| pixel(bytes).0)
+            .collect();
+        ImageBuffer::from_raw(self.width, self.height, pixels).expect("image created")
+    }
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits >> 15 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from(bits >> 10 & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff) / 1024.0;
+    sign * match exponent {
+        0 => mantissa * 2.0_f32.powi(-14),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa) * 2.0_f32.powi(exponent - 15),
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+//
+// Saving
+
+pub(crate) fn save_image(image: &DynamicImage, filename: &str) -> Result<()> {
+    let extension = Path::new(filename).extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("hdr") => {
+            let file = File::create(filename).context(format!("Create '{filename}'"))?;
+            let pixels = image.to_rgb32f().pixels().copied().collect::<Vec<Rgb<f32>>>();
+            HdrEncoder::new(BufWriter::new(file))
+                .encode(&pixels, image.width() as usize, image.height() as usize)
+        }
+        Some("exr") => DynamicImage::ImageRgba32F(image.to_rgba32f()).save(filename),
+        _ => match image {
+            DynamicImage::ImageRgba32F(linear) => {
+                let mut encoded = linear.clone();
+                for pixel in encoded.pixels_mut() {
+                    for channel in &mut pixel.0[..3] {
+                        *channel = linear_to_srgb(*channel);
+                    }
+                }
+                DynamicImage::ImageRgba32F(encoded).to_rgba8().save(filename)
+            }
+            _ => image.save(filename),
+        },
+    }.context(format!("Save image '{filename}'"))
+}
@@ -0,0 +1,115 @@
+use crate::{AttachmentConfiguration, PipelineConfiguration, RenderConfiguration, RenderPassConfiguration};
+
+//
+// AmbientOcclusion
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct AmbientOcclusion {
+    radius: f32,
+    samples: u32,
+    blur: u32,
+    intensity: f32,
+    bias: f32,
+}
+
+impl AmbientOcclusion {
+    // Target of the pass writing `ssao_geometry(position, normal)` for every fragment
+    pub const GEOMETRY: &'static str = "ssao_geometry";
+    // Input of the lit pipelines, an occlusion factor per pixel of the render target
+    pub const OCCLUSION: &'static str = "ssao";
+    pub const FUNCTIONS: &'static str = include_str!("ssao/geometry.wgsl");
+    const RAW: &'static str = "ssao_raw";
+
+    pub fn new() -> Self {
+        Self {
+            radius: 0.5,
+            samples: 16,
+            blur: 2,
+            intensity: 2.0,
+            bias: 0.1,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn with_blur(mut self, blur: u32) -> Self {
+        self.blur = blur;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    // Adds the geometry attachment and the passes turning it into the occlusion texture,
+    // the geometry pass itself is up to the application. The lit pipelines scale their ambient
+    // term by the occlusion, so it cannot come from their own depth and normals, written only as
+    // they shade. The geometry pass shares their depth buffer, which they may load rather than clear.
+    pub(crate) fn apply(self, conf: &mut RenderConfiguration) {
+        conf.add_attachment(AttachmentConfiguration::new(Self::GEOMETRY)
+            .with_format(wgpu::TextureFormat::Rgba32Float)
+            .with_filter(wgpu::FilterMode::Nearest));
+        let occlusion = |name| AttachmentConfiguration::new(name).with_format(wgpu::TextureFormat::R8Unorm);
+        conf.add_attachment(occlusion(Self::OCCLUSION));
+
+        let target = if self.blur > 0 { Self::RAW } else { Self::OCCLUSION };
+        let pipeline = PipelineConfiguration::fullscreen(&self.shader(include_str!("ssao/occlusion.wgsl")))
+            .with_input(Self::GEOMETRY)
+            .with_label("Occlusion");
+        Self::pass(conf, pipeline, "Ambient occlusion").with_target(target);
+
+        if self.blur > 0 {
+            conf.add_attachment(occlusion(Self::RAW));
+            let pipeline = PipelineConfiguration::fullscreen(&self.shader(include_str!("ssao/blur.wgsl")))
+                .with_input(Self::GEOMETRY)
+                .with_input(Self::RAW)
+                .with_label("Blur");
+            Self::pass(conf, pipeline, "Ambient occlusion blur").with_target(Self::OCCLUSION);
+        }
+    }
+
+    fn pass<'a>(conf: &'a mut RenderConfiguration, pipeline: PipelineConfiguration, label: &str) -> &'a mut RenderPassConfiguration {
+        conf.new_pass(vec![pipeline])
+            .with_label(label)
+            .with_depth(None)
+    }
+
+    fn shader(&self, source: &str) -> String {
+        format!(
+            "const SSAO_RADIUS: f32 = {:?};\n\
+             const SSAO_SAMPLES: u32 = {}u;\n\
+             const SSAO_BLUR: i32 = {};\n\
+             const SSAO_INTENSITY: f32 = {:?};\n\
+             const SSAO_BIAS: f32 = {:?};\n\
+             {}\n\
+             {source}",
+            self.radius,
+            self.samples,
+            self.blur,
+            self.intensity,
+            self.bias,
+            include_str!("ssao/common.wgsl"),
+        )
+    }
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
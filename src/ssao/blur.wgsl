@@ -0,0 +1,25 @@
+// Box blur over the noisy occlusion, skipping the background and neighbours too far away
+// from the surface to keep edges sharp.
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let coord = vec2<i32>(in.position.xy);
+    let center = ssao_geometry_at(coord);
+    if center.w == 0.0 {
+        return vec4<f32>(1.0);
+    }
+    let size = vec2<i32>(textureDimensions(ssao_raw_texture));
+    var total = 0.0;
+    var weight = 0.0;
+    for (var y = -SSAO_BLUR; y <= SSAO_BLUR; y++) {
+        for (var x = -SSAO_BLUR; x <= SSAO_BLUR; x++) {
+            let neighbour = clamp(coord + vec2<i32>(x, y), vec2<i32>(0), size - 1);
+            let geometry = ssao_geometry_at(neighbour);
+            if geometry.w > 0.0 && distance(geometry.xyz, center.xyz) < SSAO_RADIUS {
+                total += textureLoad(ssao_raw_texture, neighbour, 0).r;
+                weight += 1.0;
+            }
+        }
+    }
+    let ao = total / max(weight, 1.0);
+    return vec4<f32>(ao, ao, ao, 1.0);
+}
@@ -0,0 +1,16 @@
+fn ssao_normal(geometry: vec4<f32>) -> vec3<f32> {
+    let code = geometry.w - 1.0;
+    let upper = floor(code / 4096.0);
+    let oct = vec2<f32>(upper, code - upper * 4096.0) / 4095.0 * 2.0 - 1.0;
+    var n = vec3<f32>(oct, 1.0 - abs(oct.x) - abs(oct.y));
+    if n.z < 0.0 {
+        let folded = (1.0 - abs(n.yx)) * select(vec2<f32>(-1.0), vec2<f32>(1.0), n.xy >= vec2<f32>(0.0));
+        n = vec3<f32>(folded, n.z);
+    }
+    return normalize(n);
+}
+
+fn ssao_geometry_at(coord: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(ssao_geometry_texture));
+    return textureLoad(ssao_geometry_texture, clamp(coord, vec2<i32>(0), size - 1), 0);
+}
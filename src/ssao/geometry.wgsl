@@ -0,0 +1,12 @@
+// Packs a surface position and normal into an ambient occlusion geometry texel. The normal is
+// octahedron encoded into two 12 bit integers held exactly by the alpha channel, offset by one
+// so that a cleared texel marks the background.
+fn ssao_geometry(position: vec3<f32>, normal: vec3<f32>) -> vec4<f32> {
+    let n = normal / (abs(normal.x) + abs(normal.y) + abs(normal.z));
+    var oct = n.xy;
+    if n.z < 0.0 {
+        oct = (1.0 - abs(n.yx)) * select(vec2<f32>(-1.0), vec2<f32>(1.0), n.xy >= vec2<f32>(0.0));
+    }
+    let code = round((oct * 0.5 + 0.5) * 4095.0);
+    return vec4<f32>(position, code.x * 4096.0 + code.y + 1.0);
+}
@@ -0,0 +1,43 @@
+// World size of a pixel around the given position, from the closest neighbour with geometry.
+fn footprint(coord: vec2<i32>, position: vec3<f32>) -> f32 {
+    var offsets = array<vec2<i32>, 4>(vec2<i32>(1, 0), vec2<i32>(-1, 0), vec2<i32>(0, 1), vec2<i32>(0, -1));
+    var size = 1e9;
+    for (var i = 0; i < 4; i++) {
+        let neighbour = ssao_geometry_at(coord + offsets[i]);
+        if neighbour.w > 0.0 {
+            size = min(size, distance(neighbour.xyz, position));
+        }
+    }
+    return max(size, 1e-6);
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let coord = vec2<i32>(in.position.xy);
+    let center = ssao_geometry_at(coord);
+    if center.w == 0.0 {
+        return vec4<f32>(1.0);
+    }
+    let normal = ssao_normal(center);
+    let pixels = clamp(SSAO_RADIUS / footprint(coord, center.xyz), 2.0, 64.0);
+    let rotation = fract(sin(dot(vec2<f32>(coord), vec2<f32>(12.9898, 78.233))) * 43758.5453) * 6.283185;
+
+    // Samples spiral out to the radius, every neighbour above the tangent plane occludes
+    // in proportion to its elevation and fades out beyond twice the radius
+    var occlusion = 0.0;
+    for (var i = 0u; i < SSAO_SAMPLES; i++) {
+        let t = (f32(i) + 0.5) / f32(SSAO_SAMPLES);
+        let angle = rotation + f32(i) * 2.399963;
+        let offset = vec2<f32>(cos(angle), sin(angle)) * sqrt(t) * pixels;
+        let neighbour = ssao_geometry_at(coord + vec2<i32>(round(offset)));
+        let v = neighbour.xyz - center.xyz;
+        let dist = length(v);
+        if neighbour.w == 0.0 || dist < 1e-6 {
+            continue;
+        }
+        let falloff = clamp(2.0 - dist / SSAO_RADIUS, 0.0, 1.0);
+        occlusion += max(dot(v / dist, normal) - SSAO_BIAS, 0.0) * falloff;
+    }
+    let ao = clamp(1.0 - SSAO_INTENSITY * occlusion / f32(SSAO_SAMPLES), 0.0, 1.0);
+    return vec4<f32>(ao, ao, ao, 1.0);
+}
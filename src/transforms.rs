@@ -1,4 +1,4 @@
-use cgmath::{Matrix4, Point3, Rad, SquareMatrix, Vector3, Zero};
+use cgmath::{Angle, Matrix4, Point3, Rad, SquareMatrix, Vector3, Vector4, Zero};
 
 pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
     1.0, 0.0, 0.0, 0.0,
@@ -8,14 +8,7 @@ pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
 );
 
 #[must_use] pub fn create_projection(aspect: f32, fovy: Rad<f32>) -> Matrix4<f32> {
-    let project = if fovy > Rad::zero() {
-        cgmath::perspective(fovy, aspect, 0.1, 10.0)
-    } else {
-        let view = 1.5;
-        cgmath::ortho(-view * aspect, view * aspect, -view, view, 0.0, 10.0)
-    };
-
-    OPENGL_TO_WGPU_MATRIX * project
+    Projection::from_fovy(fovy).matrix(aspect)
 }
 
 #[must_use] pub fn create_view(eye: Point3<f32>, look_at: Point3<f32>, up: Vector3<f32>) -> Matrix4<f32> {
@@ -47,4 +40,186 @@ pub fn invert(matrix: Matrix4<f32>) -> Matrix4<f32>{
         .cast::<f64>().expect("successful cast f32 -> f64")
         .invert().expect("non-degenerate")
         .cast::<f32>().expect("successful cast f64 -> f32")
-}
\ No newline at end of file
+}
+
+//
+// Projection
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frustum {
+    Perspective { fovy: Rad<f32> },
+    Orthographic { half_height: f32 },
+    // Perspective with the edges of the near plane given explicitly, the aspect ratio is ignored
+    OffCenter { left: f32, right: f32, bottom: f32, top: f32 },
+}
+
+// Projection into the wgpu clip space, depth 0 at the near plane and 1 at the far plane,
+// or the other way round with reverse-Z. Reverse-Z needs a pass set up with `with_depth_projection`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct Projection {
+    frustum: Frustum,
+    near: f32,
+    // None for an infinite far plane
+    far: Option<f32>,
+    reverse_z: bool,
+}
+
+impl Projection {
+    const ORTHO_HALF_HEIGHT: f32 = 1.5;
+
+    pub fn perspective<F: Into<Rad<f32>>>(fovy: F) -> Self {
+        Self { frustum: Frustum::Perspective { fovy: fovy.into() }, near: 0.1, far: Some(10.0), reverse_z: false }
+    }
+
+    pub fn orthographic(half_height: f32) -> Self {
+        Self { frustum: Frustum::Orthographic { half_height }, near: 0.0, far: Some(10.0), reverse_z: false }
+    }
+
+    pub fn off_center(left: f32, right: f32, bottom: f32, top: f32) -> Self {
+        Self { frustum: Frustum::OffCenter { left, right, bottom, top }, ..Self::perspective(Rad::turn_div_4()) }
+    }
+
+    // Perspective for a positive field of view, the orthographic view of the examples otherwise
+    pub fn from_fovy(fovy: Rad<f32>) -> Self {
+        if fovy > Rad::zero() {
+            Self::perspective(fovy)
+        } else {
+            Self::orthographic(Self::ORTHO_HALF_HEIGHT)
+        }
+    }
+
+    pub fn with_near(mut self, near: f32) -> Self {
+        self.near = near;
+        self
+    }
+
+    pub fn with_far(mut self, far: f32) -> Self {
+        self.far = Some(far);
+        self
+    }
+
+    // Only perspective frusta can reach infinity
+    pub fn with_infinite_far(mut self) -> Self {
+        assert!(
+            !matches!(self.frustum, Frustum::Orthographic { .. }),
+            "Orthographic projections need a finite far plane",
+        );
+        self.far = None;
+        self
+    }
+
+    pub fn with_reverse_z(mut self, reverse_z: bool) -> Self {
+        self.reverse_z = reverse_z;
+        self
+    }
+
+    // Replaces the field of view of a perspective projection, other frusta are kept
+    pub fn with_fovy(mut self, fovy: Rad<f32>) -> Self {
+        if let Frustum::Perspective { .. } = self.frustum {
+            self.frustum = Frustum::Perspective { fovy };
+        }
+        self
+    }
+
+    // Replaces the height of an orthographic projection, other frusta are kept
+    pub fn with_half_height(mut self, half_height: f32) -> Self {
+        if let Frustum::Orthographic { .. } = self.frustum {
+            self.frustum = Frustum::Orthographic { half_height };
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn frustum(&self) -> Frustum {
+        self.frustum
+    }
+
+    // Field of view of a perspective projection, zero for the other frusta
+    #[must_use]
+    pub fn fovy(&self) -> Rad<f32> {
+        match self.frustum {
+            Frustum::Perspective { fovy } => fovy,
+            Frustum::Orthographic { .. } | Frustum::OffCenter { .. } => Rad::zero(),
+        }
+    }
+
+    #[must_use]
+    pub fn near(&self) -> f32 {
+        self.near
+    }
+
+    #[must_use]
+    pub fn far(&self) -> Option<f32> {
+        self.far
+    }
+
+    #[must_use]
+    pub fn is_reverse_z(&self) -> bool {
+        self.reverse_z
+    }
+
+    // Half of the visible height at the distance from the eye
+    #[must_use]
+    pub fn half_height(&self, distance: f32) -> f32 {
+        match self.frustum {
+            Frustum::Perspective { fovy } => distance * (fovy / 2.0).tan(),
+            Frustum::Orthographic { half_height } => half_height,
+            Frustum::OffCenter { bottom, top, .. } => distance * (top - bottom) / (2.0 * self.near),
+        }
+    }
+
+    #[must_use]
+    pub fn depth_compare(&self) -> wgpu::CompareFunction {
+        if self.reverse_z { wgpu::CompareFunction::GreaterEqual } else { wgpu::CompareFunction::LessEqual }
+    }
+
+    #[must_use]
+    pub fn depth_clear(&self) -> f32 {
+        if self.reverse_z { 0.0 } else { 1.0 }
+    }
+
+    #[must_use]
+    pub fn matrix(&self, aspect: f32) -> Matrix4<f32> {
+        let (near, reverse_z) = (self.near, self.reverse_z);
+        match self.frustum {
+            Frustum::Perspective { fovy } => {
+                let y = 1.0 / (fovy / 2.0).tan();
+                self.perspective_matrix(y / aspect, y, 0.0, 0.0)
+            }
+            Frustum::OffCenter { left, right, bottom, top } => self.perspective_matrix(
+                2.0 * near / (right - left),
+                2.0 * near / (top - bottom),
+                (right + left) / (right - left),
+                (top + bottom) / (top - bottom),
+            ),
+            Frustum::Orthographic { half_height } => {
+                let far = self.far.expect("Orthographic projections need a finite far plane");
+                let depth = far - near;
+                let (z, w) = if reverse_z { (1.0 / depth, far / depth) } else { (-1.0 / depth, -near / depth) };
+                Matrix4::from_cols(
+                    Vector4::new(1.0 / (half_height * aspect), 0.0, 0.0, 0.0),
+                    Vector4::new(0.0, 1.0 / half_height, 0.0, 0.0),
+                    Vector4::new(0.0, 0.0, z, 0.0),
+                    Vector4::new(0.0, 0.0, w, 1.0),
+                )
+            }
+        }
+    }
+
+    fn perspective_matrix(&self, x: f32, y: f32, x_offset: f32, y_offset: f32) -> Matrix4<f32> {
+        let near = self.near;
+        let (z, w) = match (self.far, self.reverse_z) {
+            (Some(far), false) => (far / (near - far), near * far / (near - far)),
+            (Some(far), true) => (near / (far - near), near * far / (far - near)),
+            (None, false) => (-1.0, -near),
+            (None, true) => (0.0, near),
+        };
+        Matrix4::from_cols(
+            Vector4::new(x, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, y, 0.0, 0.0),
+            Vector4::new(x_offset, y_offset, z, -1.0),
+            Vector4::new(0.0, 0.0, w, 0.0),
+        )
+    }
+}
@@ -26,12 +26,12 @@ impl Uniforms {
         min_binding_size: None,
     };
 
-    pub fn new(conf: UniformsConfiguration, wg: &WebGPUDevice) -> Self {
+    pub fn new(conf: UniformsConfiguration, wg: &WebGPUDevice, group: u32) -> Self {
         let UniformsConfiguration {uniforms, variants, instances } = conf;
         let (buffers, declarations): (Vec<SmartBuffer<wgpu::ShaderStages>>, Vec<String>) =
             uniforms.into_iter()
                 .enumerate()
-                .map(|(index, uniform)| uniform.resolve(wg, index))
+                .map(|(index, uniform)| uniform.resolve(wg, group, index))
                 .unzip();
         let bindings = buffers.iter()
             .map(|buffer| Binding {
@@ -67,14 +67,14 @@ impl<T: Clone> To<T> for T {
 pub(crate) struct UniformConfig {
     pub(crate) buffer: SmartBufferDescriptor<wgpu::ShaderStages>,
     pub(crate) writer: Rc<RefCell<Option<BufferWriter>>>,
-    pub(crate) declaration: fn(usize) -> String,
+    pub(crate) declaration: fn(u32, usize) -> String,
 }
 
 impl UniformConfig {
-    pub(crate) fn resolve(self, wg: &WebGPUDevice, index: usize) -> (SmartBuffer<wgpu::ShaderStages>, String) {
+    pub(crate) fn resolve(self, wg: &WebGPUDevice, group: u32, index: usize) -> (SmartBuffer<wgpu::ShaderStages>, String) {
         let buffer = self.buffer.create_buffer(wg);
         *(self.writer.borrow_mut()) = Some(buffer.writer(wg.queue.clone()));
-        (buffer, (self.declaration)(index))
+        (buffer, (self.declaration)(group, index))
     }
 }
 
@@ -89,7 +89,7 @@ pub struct UniformsConfiguration {
 }
 
 impl UniformsConfiguration {
-    pub fn add<T>(&mut self, label: &str, value: T, stages: wgpu::ShaderStages) -> UniformAdd<T> {
+    pub fn add<T>(&mut self, label: &str, value: T, stages: wgpu::ShaderStages) -> UniformAdd<'_, T> {
         UniformAdd {
             uniforms: &mut self.uniforms,
             label: label.to_owned(),
@@ -98,6 +98,10 @@ impl UniformsConfiguration {
         }
     }
 
+    pub(crate) fn is_empty(&self) -> bool {
+        self.uniforms.is_empty()
+    }
+
     pub fn instances(&mut self, instances: usize) -> &mut Self {
         self.instances = instances;
         self
@@ -139,7 +143,7 @@ impl<'a, T> UniformAdd<'a, T> {
             self.uniforms.push(UniformConfig {
                 buffer,
                 writer: uniform.buffer.clone(),
-                declaration: |i| B::uniform_declaration(i),
+                declaration: |group, binding| B::uniform_declaration(group, binding),
             });
             uniform
         } else {
@@ -184,11 +188,12 @@ pub trait UniformInfo: Pod {
     const TYPES: &'static str = "";
 
     #[must_use]
-    fn uniform_declaration(binding: usize) -> String {
+    fn uniform_declaration(group: u32, binding: usize) -> String {
         let struct_declaration = Self::struct_declaration();
         format!(
-            "{}\n@group(0) @binding({}) var<uniform> {}: {};\n",
+            "{}\n@group({}) @binding({}) var<uniform> {}: {};\n",
             struct_declaration,
+            group,
             binding,
             Self::BINDING_NAME,
             Self::STRUCT_NAME,
@@ -205,7 +210,8 @@ pub trait UniformInfo: Pod {
             .join("\n");
 
         let struct_declaration = format!(
-            "struct {} {{\n{}\n}}\n{}",
+            "{}struct {} {{\n{}\n}}\n{}",
+            Self::TYPES,
             Self::STRUCT_NAME,
             attributes,
             Self::FUNCTIONS,
@@ -224,6 +230,8 @@ impl<U: UniformInfo, const L: usize> UniformInfo for [U; L] {
     const STRUCT_NAME: &'static str = U::STRUCT_NAME;
     const BINDING_NAME: &'static str = U::BINDING_NAME;
     const ATTRIBUTES: &'static [(&'static str, &'static str)] = U::ATTRIBUTES;
+    const FUNCTIONS: &'static str = U::FUNCTIONS;
+    const TYPES: &'static str = U::TYPES;
 }
 
 
@@ -249,7 +257,7 @@ impl<T> Uniform<T> {
         }
     }
 
-    pub fn as_mut(&mut self) -> UniformMut<T> {
+    pub fn as_mut(&mut self) -> UniformMut<'_, T> {
         UniformMut { uniform: self }
     }
 }
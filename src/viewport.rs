@@ -0,0 +1,175 @@
+use winit::dpi::PhysicalPosition;
+use winit::event::ElementState;
+
+use crate::Content;
+use crate::clock::FrameTime;
+use crate::input::{InputEvent, InputState};
+use crate::poster::Tile;
+
+//
+// Viewport
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[must_use]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub scissor: bool,
+}
+
+impl Viewport {
+    pub const FULL: Viewport = Viewport { x: 0.0, y: 0.0, width: 1.0, height: 1.0, scissor: false };
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height, scissor: false }
+    }
+
+    pub const fn with_scissor(mut self, scissor: bool) -> Self {
+        self.scissor = scissor;
+        self
+    }
+
+    pub(crate) fn pixels(&self, width: u32, height: u32) -> PixelRect {
+        let left = Self::scale(self.x, width);
+        let top = Self::scale(self.y, height);
+        let right = Self::scale(self.x + self.width, width).max(left);
+        let bottom = Self::scale(self.y + self.height, height).max(top);
+        PixelRect { x: left, y: top, width: right - left, height: bottom - top }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn scale(value: f32, size: u32) -> u32 {
+        (value.clamp(0.0, 1.0) * size as f32).round() as u32
+    }
+
+    pub(crate) fn apply(&self, render_pass: &mut wgpu::RenderPass, width: u32, height: u32) -> bool {
+        let rect = self.pixels(width, height);
+        if rect.width == 0 || rect.height == 0 {
+            return false;
+        }
+        render_pass.set_viewport(
+            rect.x as f32,
+            rect.y as f32,
+            rect.width as f32,
+            rect.height as f32,
+            0.0,
+            1.0,
+        );
+        if self.scissor {
+            render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+        } else {
+            render_pass.set_scissor_rect(0, 0, width, height);
+        }
+        true
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+//
+// PixelRect
+
+pub(crate) struct PixelRect {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+//
+// ViewportContent
+
+// Passes the cursor on relative to the viewport. Pointer events outside of it are dropped,
+// unless a button was pressed inside and is still held.
+pub(crate) struct ViewportContent<T> {
+    viewport: Viewport,
+    content: Box<dyn Content<T>>,
+    size: (u32, u32),
+    hovered: bool,
+    dragging: bool,
+}
+
+impl<T> ViewportContent<T> {
+    pub(crate) fn new(viewport: Viewport, content: Box<dyn Content<T>>) -> Self {
+        Self { viewport, content, size: (0, 0), hovered: false, dragging: false }
+    }
+
+    // Cursor event in viewport coordinates, and whether the cursor is inside
+    #[allow(clippy::cast_possible_truncation)]
+    fn local(&self, position: PhysicalPosition<f64>, normalized: [f32; 2]) -> (InputEvent, bool) {
+        let rect = self.viewport.pixels(self.size.0, self.size.1);
+        let normalized = [
+            (normalized[0] - self.viewport.x) / self.viewport.width,
+            (normalized[1] - self.viewport.y) / self.viewport.height,
+        ];
+        let position = PhysicalPosition::new(position.x - f64::from(rect.x), position.y - f64::from(rect.y));
+        let inside = normalized.iter().all(|value| (0.0..1.0).contains(value));
+        (InputEvent::CursorMoved { position, normalized }, inside)
+    }
+}
+
+impl<T> Content<T> for ViewportContent<T> {
+    fn resize(&mut self, context: T, width: u32, height: u32) {
+        self.size = (width, height);
+        let rect = self.viewport.pixels(width, height);
+        if rect.width > 0 && rect.height > 0 {
+            self.content.resize(context, rect.width, rect.height);
+        }
+    }
+
+    fn update(&mut self, context: T, time: &FrameTime, input: &InputState) {
+        let mut local = input.clone();
+        local.apply(&self.local(input.cursor(), input.normalized_cursor()).0);
+        self.content.update(context, time, &local);
+    }
+
+    fn redraw(&mut self, context: T) {
+        self.content.redraw(context);
+    }
+
+    fn input(&mut self, context: T, event: &InputEvent) {
+        let pointer = self.hovered || self.dragging;
+        match *event {
+            InputEvent::CursorMoved { position, normalized } => {
+                let (local, inside) = self.local(position, normalized);
+                self.hovered = inside;
+                if inside || self.dragging {
+                    self.content.input(context, &local);
+                }
+            }
+            InputEvent::MouseButton { state: ElementState::Pressed, .. } if self.hovered => {
+                self.dragging = true;
+                self.content.input(context, event);
+            }
+            InputEvent::MouseButton { state: ElementState::Released, .. } => {
+                self.dragging = false;
+                self.content.input(context, event);
+            }
+            InputEvent::MouseButton { .. }
+            | InputEvent::MouseMotion { .. }
+            | InputEvent::Scroll { .. }
+            | InputEvent::Magnify { .. } => {
+                if pointer {
+                    self.content.input(context, event);
+                }
+            }
+            InputEvent::Focused(focused) => {
+                self.dragging &= focused;
+                self.content.input(context, event);
+            }
+            InputEvent::Key { .. } => self.content.input(context, event),
+        }
+    }
+
+    fn tile(&mut self, context: T, tile: &Tile) {
+        if let Some((_, local)) = tile.clip(&self.viewport) {
+            self.content.tile(context, &local);
+        }
+    }
+}
@@ -1,19 +1,29 @@
-use core::mem::size_of;
 use core::time::Duration;
 use core::cell::RefCell;
 use std::rc::Rc;
-use std::sync::Arc;
-
-use anyhow::Result;
-
-use crate::{CompositeContent, Content, PipelineConfiguration, RawWindow, RenderConfiguration, RenderPassConfiguration, SmartBuffer, usize_as_u32};
-use crate::bindings::Textures;
-use crate::uniforms::Uniforms;
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+use image::DynamicImage;
+
+use crate::{CompositeContent, ComputeConfiguration, Content, FrameTime, InputEvent, InputState, PipelineConfiguration, RawWindow, RenderConfiguration, SmartBuffer, usize_as_u32};
+use crate::bindings::{storage_binding, storage_declaration, BindGroupVariants, Textures};
+use crate::device::DeviceConfiguration;
+use crate::attachments::Attachments;
+use crate::feedback::Feedbacks;
+use crate::frame::TargetUniform;
+use crate::graph::{GraphPass, Input, Resource, Resources};
+use crate::post::PostProcessing;
+use crate::poster::Tile;
+use crate::profiler::FrameProfiler;
+use crate::screenshot::{Readback, Screenshots};
+use crate::uniforms::{Uniform, Uniforms};
+use crate::viewport::{Viewport, ViewportContent};
 
 type ContentBox = Box<dyn Content<()>>;
 
 pub(crate) struct WebGPUDevice {
-    surface: wgpu::Surface,
+    surface: Option<wgpu::Surface>,
     surface_config: wgpu::SurfaceConfiguration,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: Rc<wgpu::Queue>,
@@ -21,34 +31,35 @@ pub(crate) struct WebGPUDevice {
 }
 
 impl WebGPUDevice {
-    async fn new(window: &dyn RawWindow) -> Self {
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+    async fn new(window: Option<&dyn RawWindow>, conf: &DeviceConfiguration) -> Self {
+        let instance = conf.instance();
         // SAFETY: Valid window handle provided
-        let surface = unsafe { instance.create_surface(&window) }.expect("Create surface");
+        let surface = window.map(|window| unsafe { instance.create_surface(&window) }.expect("Create surface"));
 
         let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
+            .request_adapter(&conf.adapter_options(surface.as_ref()))
             .await
             .expect("Failed to find an appropriate adapter");
 
-        let format = *surface.get_capabilities(&adapter)
-            .formats.get(0).expect("at least one compatible format");
+        let (format, present_mode, alpha_mode) = match surface.as_ref() {
+            Some(surface) => {
+                let capabilities = surface.get_capabilities(&adapter);
+                (conf.format(&capabilities), conf.present_mode(&capabilities), conf.alpha_mode(&capabilities))
+            }
+            None => (conf.offscreen_format(), wgpu::PresentMode::Fifo, wgpu::CompositeAlphaMode::Opaque),
+        };
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format,
             width: 0,
             height: 0,
-            present_mode: wgpu::PresentMode::Mailbox,
-            alpha_mode: wgpu::CompositeAlphaMode::default(),
+            present_mode,
+            alpha_mode,
             view_formats: vec![],
         };
 
         let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .request_device(&conf.device_descriptor(&adapter), None)
             .await
             .expect("Failed to create device");
         WebGPUDevice {
@@ -63,10 +74,12 @@ impl WebGPUDevice {
     fn resize(&mut self, width: u32, height: u32) {
         self.surface_config.width = width;
         self.surface_config.height = height;
-        self.surface.configure(&self.device, &self.surface_config);
+        if let Some(surface) = self.surface.as_ref() {
+            surface.configure(&self.device, &self.surface_config);
+        }
     }
 
-    fn create_texture(&self, label: &str, width: u32, height: u32, usage: wgpu::TextureUsages, format: wgpu::TextureFormat) -> wgpu::Texture {
+    pub(crate) fn create_texture(&self, label: &str, width: u32, height: u32, usage: wgpu::TextureUsages, format: wgpu::TextureFormat) -> wgpu::Texture {
         self.device.create_texture(&wgpu::TextureDescriptor {
             size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
             mip_level_count: 1,
@@ -85,63 +98,245 @@ impl WebGPUDevice {
 
 pub(crate) struct WebGPURender {
     wg: WebGPUDevice,
+    contents: CompositeContent<()>,
     render_passes: Vec<RenderPass>,
-    save_image: Option<SaveImage>,
-    prev_duration: f32,
+    resources: Resources,
+    phase: usize,
+    depth_buffers: RefCell<DepthBuffers>,
+    save_image: Option<String>,
+    screenshots: Screenshots,
+    offscreen: RefCell<Option<wgpu::Texture>>,
+    blit: Option<Blit>,
+    profiler: RefCell<Option<FrameProfiler>>,
+    fps: FpsCounter,
 }
 
 impl WebGPURender {
     pub fn content<'a>(window: &dyn RawWindow, conf: RenderConfiguration) -> Result<Box<dyn Content<()> + 'a>> {
-        pollster::block_on(Self::content_async(window, conf))
+        Ok(Box::new(pollster::block_on(Self::new(Some(window), conf))?))
     }
 
-    pub async fn content_async<'a>(
-        window: &dyn RawWindow,
-        conf: RenderConfiguration,
-    ) -> Result<Box<dyn Content<()> + 'a>> {
-        let wg = WebGPUDevice::new(window).await;
+    pub async fn new(
+        window: Option<&dyn RawWindow>,
+        mut conf: RenderConfiguration,
+    ) -> Result<Self> {
+        let mut device = conf.device.clone();
+        if conf.profiler.is_some() {
+            device.optional_features |= FrameProfiler::features();
+        }
+        let wg = WebGPUDevice::new(window, &device).await;
+        if let Some(ambient_occlusion) = conf.ambient_occlusion.take() {
+            ambient_occlusion.apply(&mut conf);
+        }
+        if let Some(post) = conf.post.take() {
+            post.apply(&mut conf, wg.texture_format);
+        }
+        PostProcessing::resolve_formats(&mut conf, wg.texture_format);
+
+        let frame_uniform = conf.render_passes.iter()
+            .any(|pass| pass.pipelines.iter().any(|pipeline| pipeline.frame_uniform)
+                || pass.compute.as_ref().is_some_and(|compute| compute.frame_uniform));
+        if frame_uniform {
+            conf.with_frame_uniform();
+        }
 
+        let shared = (!conf.uniforms.is_empty())
+            .then(|| Rc::new(Uniforms::new(conf.uniforms, &wg, Pipeline::SHARED_GROUP)));
+        let storage = conf.render_passes.iter()
+            .filter(|pass| pass.compute.is_some())
+            .filter_map(|pass| pass.target.clone())
+            .collect::<Vec<_>>();
+        let resources = Resources {
+            feedbacks: Feedbacks::new(&wg, conf.feedbacks, &storage)?,
+            attachments: Attachments::new(&wg, conf.attachments, wg.texture_format)?,
+            surface_format: wg.texture_format,
+        };
         let (render_passes, contents_2d): (Vec<RenderPass>, Vec<Vec<ContentBox>>) =
-            conf.render_passes.into_iter()
-                .map(|render_pass| RenderPass::new(render_pass, &wg))
+            resources.order(conf.render_passes)?.into_iter()
+                .enumerate()
+                .map(|(index, render_pass)| RenderPass::new(render_pass, index, &wg, shared.as_ref(), &resources))
                 .collect::<Result<Vec<_>>>()?
                 .into_iter()
                 .unzip();
 
-        let mut contents: Vec<ContentBox> = contents_2d.into_iter().flatten().collect();
-        let save_image = conf.save_image.map(SaveImage::new);
-        contents.push(Box::new(WebGPURender {
+        let profiler = conf.profiler.map(|handle| FrameProfiler::new(
+            handle,
+            render_passes.iter().map(RenderPass::labels).collect(),
+            &wg.device,
+            &wg.queue,
+        ));
+
+        Ok(WebGPURender {
             wg,
+            contents: CompositeContent {
+                parts: conf.listeners.into_iter().chain(contents_2d.into_iter().flatten()).collect(),
+            },
             render_passes,
-            save_image,
-            prev_duration: 0.0,
-        }));
-
-        Ok(Box::new(CompositeContent { parts: contents }))
+            resources,
+            phase: 0,
+            depth_buffers: RefCell::new(DepthBuffers::default()),
+            save_image: conf.save_image,
+            screenshots: Screenshots::new(conf.screenshot, conf.screenshot_key),
+            offscreen: RefCell::new(None),
+            blit: None,
+            profiler: RefCell::new(profiler),
+            fps: FpsCounter::new(),
+        })
     }
 
     fn render(&mut self) {
-        self.render_to_surface();
+        let mut requests = self.screenshots.requests();
+        if let Some(filename) = self.save_image.as_ref() {
+            if !self.screenshots.is_pending(filename) && !requests.contains(filename) {
+                requests.push(filename.clone());
+            }
+        }
 
-        self.save_image.iter().for_each(|save_image|
-            save_image.render(self, self.wg.surface_config.width, self.wg.surface_config.height));
+        self.prepare(self.wg.surface_config.width, self.wg.surface_config.height);
+        if requests.is_empty() {
+            self.render_to_surface();
+        } else {
+            self.render_screenshots(requests);
+        }
+
+        self.screenshots.submitted();
+        self.screenshots.poll(&self.wg.device);
+        self.poll_profiler();
+        self.swap_feedbacks();
+    }
+
+    fn prepare(&mut self, width: u32, height: u32) {
+        if self.resources.attachments.resize(&self.wg, width, height) {
+            for render_pass in &mut self.render_passes {
+                for pipeline in &mut render_pass.pipelines {
+                    pipeline.bind(&self.wg, &self.resources);
+                }
+                if let Some(compute) = render_pass.compute.as_mut() {
+                    compute.bind(&self.wg, &self.resources);
+                }
+            }
+        }
+        for render_pass in &mut self.render_passes {
+            let (width, height) = self.resources.size(render_pass.target, width, height);
+            for pipeline in &mut render_pass.pipelines {
+                pipeline.resize_target(width, height);
+            }
+        }
+    }
+
+    fn swap_feedbacks(&mut self) {
+        self.phase = (self.phase + 1) % Feedbacks::PHASES;
+    }
+
+    fn poll_profiler(&mut self) {
+        if let Some(profiler) = self.profiler.get_mut() {
+            profiler.submitted();
+            profiler.poll(&self.wg.device);
+        }
     }
 
     fn render_to_surface(&self) {
-        let frame = self.wg.surface.get_current_texture().expect("Current texture");
-        let encoder = self.render_to_texture(&frame.texture);
-        self.wg.queue.submit(Some(encoder.finish()));
-        frame.present();
+        if let Some(surface) = self.wg.surface.as_ref() {
+            let frame = surface.get_current_texture().expect("Current texture");
+            let encoder = self.render_to_texture(&frame.texture, &Tile::FULL, true);
+            self.wg.queue.submit(Some(encoder.finish()));
+            frame.present();
+        }
+    }
+
+    // Renders the frame once offscreen, copies it for the screenshots and blits it to the surface
+    fn render_screenshots(&mut self, requests: Vec<String>) {
+        let texture = self.offscreen(self.wg.surface_config.width, self.wg.surface_config.height);
+        let mut encoder = self.render_to_texture(&texture, &Tile::FULL, true);
+        self.screenshots.copy(&self.wg.device, &mut encoder, &texture, requests);
+        match self.wg.surface.as_ref() {
+            Some(surface) => {
+                let frame = surface.get_current_texture().expect("Current texture");
+                self.blit.get_or_insert_with(|| Blit::new(&self.wg)).copy(&self.wg, &mut encoder, &texture, &frame.texture);
+                self.wg.queue.submit(Some(encoder.finish()));
+                frame.present();
+            }
+            None => {
+                self.wg.queue.submit(Some(encoder.finish()));
+            }
+        }
+        *self.offscreen.borrow_mut() = Some(texture);
+    }
+
+    pub(crate) fn capture_frame(&mut self) -> Result<DynamicImage> {
+        self.contents.redraw(());
+        let image = self.capture(self.wg.surface_config.width, self.wg.surface_config.height, &Tile::FULL, true);
+        self.poll_profiler();
+        self.swap_feedbacks();
+        image
+    }
+
+    pub(crate) fn capture_tile(&mut self, tile: &Tile, width: u32, height: u32) -> Result<DynamicImage> {
+        self.contents.tile((), tile);
+        self.contents.redraw(());
+        self.capture(width, height, tile, false)
+    }
+
+    pub(crate) fn max_texture_size(&self) -> u32 {
+        self.wg.device.limits().max_texture_dimension_2d
+    }
+
+    fn capture(&mut self, width: u32, height: u32, tile: &Tile, profile: bool) -> Result<DynamicImage> {
+        self.prepare(width, height);
+        let texture = self.offscreen(width, height);
+        let readback = Readback::new(width, height, texture.format());
+        let buffer = readback.buffer(&self.wg.device);
+        let mut encoder = self.render_to_texture(&texture, tile, profile);
+        readback.copy(&mut encoder, &texture, &buffer);
+        let submission = self.wg.queue.submit(Some(encoder.finish()));
+        *self.offscreen.borrow_mut() = Some(texture);
+        readback.read(&self.wg.device, &buffer, submission)
     }
 
-    fn render_to_texture(&self, texture: &wgpu::Texture) -> wgpu::CommandEncoder {
+    fn offscreen(&self, width: u32, height: u32) -> wgpu::Texture {
+        match self.offscreen.borrow_mut().take() {
+            Some(texture) if texture.width() == width && texture.height() == height => texture,
+            _ => self.wg.create_texture(
+                "Offscreen",
+                width,
+                height,
+                wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                self.wg.texture_format,
+            ),
+        }
+    }
+
+    fn render_to_texture(&self, texture: &wgpu::Texture, tile: &Tile, profile: bool) -> wgpu::CommandEncoder {
         let wg = &self.wg;
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         let mut encoder: wgpu::CommandEncoder = wg.device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
         {
+            let mut depth_buffers = self.depth_buffers.borrow_mut();
+            depth_buffers.begin_frame();
+            let mut profiler = self.profiler.borrow_mut();
+            let mut profiler = profiler.as_mut().filter(|_| profile);
+            if let Some(profiler) = profiler.as_deref_mut() {
+                profiler.begin_frame();
+            }
             for render_pass in &self.render_passes {
-                render_pass.render(wg, &mut encoder, &view, texture.width(), texture.height());
+                let (view, width, height, tile) = match render_pass.target {
+                    Resource::Surface => (&view, texture.width(), texture.height(), tile),
+                    Resource::Feedback(index) => {
+                        let feedback = self.resources.feedbacks.get(index);
+                        (feedback.target(self.phase), feedback.width, feedback.height, &Tile::FULL)
+                    }
+                    Resource::Attachment(index) => {
+                        let (view, width, height) = self.resources.attachments.get(index).target();
+                        (view, width, height, tile)
+                    }
+                };
+                let depth = render_pass.depth.as_ref()
+                    .map(|depth| (depth, depth_buffers.view(wg, depth.format, width, height)));
+                render_pass.render(&mut encoder, view, width, height, tile, depth, self.phase, profiler.as_deref_mut());
+            }
+            if let Some(profiler) = profiler {
+                profiler.end_frame(&mut encoder);
             }
         }
         encoder
@@ -149,133 +344,334 @@ impl WebGPURender {
 }
 
 impl Content<()> for WebGPURender {
-    fn resize(&mut self, _context: (), width: u32, height: u32) {
+    fn resize(&mut self, context: (), width: u32, height: u32) {
+        self.contents.resize(context, width, height);
         if width > 0 && height > 0 {
             self.wg.resize(width, height);
         }
     }
 
-    fn update(&mut self, _context: (), dt: Duration) {
-        let now = dt.as_secs_f32();
-        println!("{:.1} fps", 1.0 / (now - self.prev_duration));
-        self.prev_duration = now;
+    fn update(&mut self, context: (), time: &FrameTime, input: &InputState) {
+        self.contents.update(context, time, input);
+    }
+
+    fn redraw(&mut self, context: ()) {
+        self.contents.redraw(context);
+        self.fps.frame();
         self.render();
     }
+
+    fn input(&mut self, context: (), event: &InputEvent) {
+        self.screenshots.input(event);
+        self.contents.input(context, event);
+    }
+}
+
+//
+// FpsCounter
+
+struct FpsCounter {
+    start: Instant,
+    frames: u32,
+}
+
+impl FpsCounter {
+    const PERIOD: Duration = Duration::from_secs(1);
+
+    fn new() -> Self {
+        Self { start: Instant::now(), frames: 0 }
+    }
+
+    fn frame(&mut self) {
+        self.frames += 1;
+        let elapsed = self.start.elapsed();
+        if elapsed >= Self::PERIOD {
+            println!("{:.1} fps", self.frames as f32 / elapsed.as_secs_f32());
+            *self = Self::new();
+        }
+    }
+}
+
+//
+// Blit
+
+struct Blit {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+}
+
+impl Blit {
+    fn new(wg: &WebGPUDevice) -> Self {
+        let layout = wg.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Blit Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                },
+                count: None,
+            }],
+        });
+        let pipeline = Pipeline::create_pipeline(
+            &wg.device,
+            wg.texture_format.into(),
+            &[],
+            &[&layout],
+            include_str!("blit.wgsl"),
+            wgpu::PrimitiveState::default(),
+            None,
+        );
+        Self { pipeline, layout }
+    }
+
+    fn copy(&self, wg: &WebGPUDevice, encoder: &mut wgpu::CommandEncoder, source: &wgpu::Texture, target: &wgpu::Texture) {
+        let source = source.create_view(&wgpu::TextureViewDescriptor::default());
+        let target = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let group = wg.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Blit Bind Group"),
+            layout: &self.layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&source) }],
+        });
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: true },
+            })],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
 }
 
 //
 // RenderPass
 
 struct RenderPass {
+    index: usize,
+    label: String,
     pipelines: Vec<Pipeline>,
+    compute: Option<ComputePipeline>,
     load: wgpu::LoadOp<wgpu::Color>,
     depth: Option<Depth>,
+    target: Resource,
 }
 
 impl RenderPass {
-    fn new(conf: RenderPassConfiguration, wg: &WebGPUDevice)
-        -> Result<(RenderPass, Vec<ContentBox>)>
-    {
-        let depth = conf.depth.map(|depth_conf| Depth { format: depth_conf.format });
-        let (pipelines, listeners): (Vec<Pipeline>, Vec<Vec<ContentBox>>) =
+    fn new(
+        graph_pass: GraphPass,
+        index: usize,
+        wg: &WebGPUDevice,
+        shared: Option<&Rc<Uniforms>>,
+        resources: &Resources,
+    ) -> Result<(RenderPass, Vec<ContentBox>)> {
+        let GraphPass { conf, target, inputs } = graph_pass;
+        let label = conf.label.unwrap_or_else(|| format!("Pass {index}"));
+        let format = resources.format(target);
+        let depth = conf.depth.map(|depth_conf| Depth {
+            format: depth_conf.format(),
+            ops: wgpu::Operations { load: depth_conf.load, store: depth_conf.store },
+            compare: depth_conf.compare,
+        });
+        let mut inputs = inputs.into_iter();
+        let (pipelines, mut listeners): (Vec<Pipeline>, Vec<Vec<ContentBox>>) =
             conf.pipelines.into_iter()
-                .map(|pipeline| Pipeline::new(
-                    pipeline,
-                    wg,
-                    depth.as_ref().map(Depth::stencil),
-                ))
+                .zip(inputs.by_ref())
+                .enumerate()
+                .map(|(index, (pipeline, inputs))| {
+                    let viewport = pipeline.viewport.unwrap_or(conf.viewport);
+                    let depth_stencil = depth.as_ref().map(|depth| depth.stencil(pipeline.depth_test));
+                    Pipeline::new(pipeline, index, wg, shared.cloned(), inputs, resources, format, depth_stencil, viewport)
+                })
                 .collect::<Result<Vec<_>>>()?
                 .into_iter().unzip();
+        let compute = match conf.compute {
+            Some(compute) => {
+                let inputs = inputs.next().unwrap_or_default();
+                let (compute, compute_listeners) = ComputePipeline::new(compute, wg, shared.cloned(), inputs, resources, target)?;
+                listeners.push(compute_listeners);
+                Some(compute)
+            }
+            None => None,
+        };
         Ok((
-            RenderPass { pipelines, load: conf.load, depth },
+            RenderPass { index, label, pipelines, compute, load: conf.load, depth, target },
             listeners.into_iter().flatten().collect(),
         ))
     }
 
+    fn labels(&self) -> (String, Vec<String>) {
+        let pipelines = self.pipelines.iter().map(|pipeline| &pipeline.label)
+            .chain(self.compute.iter().map(|compute| &compute.label));
+        (self.label.clone(), pipelines.cloned().collect())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn render(
         &self,
-        wg: &WebGPUDevice,
         encoder: &mut wgpu::CommandEncoder,
         view: &wgpu::TextureView,
         width: u32,
-        height: u32
+        height: u32,
+        tile: &Tile,
+        depth: Option<(&Depth, Rc<wgpu::TextureView>)>,
+        phase: usize,
+        mut profiler: Option<&mut FrameProfiler>,
     ) {
-        let depth = self.depth.as_ref().map(|depth| depth.begin_render_pass(wg, width, height));
+        if let Some(profiler) = profiler.as_deref_mut() {
+            profiler.begin_pass(encoder, self.index);
+        }
+        if let Some(compute) = self.compute.as_ref() {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some(&self.label) });
+            if let Some(profiler) = profiler.as_deref_mut() {
+                profiler.begin_compute(&mut compute_pass, self.index, 0);
+            }
+            compute.dispatch(&mut compute_pass, phase);
+            if let Some(profiler) = profiler.as_deref_mut() {
+                profiler.end_compute(&mut compute_pass);
+            }
+            drop(compute_pass);
+            if let Some(profiler) = profiler {
+                profiler.end_pass(encoder);
+            }
+            return;
+        }
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: None,
+            label: Some(&self.label),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view,
                 resolve_target: None,
                 ops: wgpu::Operations { load: self.load, store: true },
             })],
-            depth_stencil_attachment: depth.as_ref().map(RuntimeDepth::attachment),
+            depth_stencil_attachment: depth.as_ref()
+                .map(|&(depth, ref view)| depth.attachment(view)),
         });
 
-        for pipeline in &self.pipelines {
-            pipeline.render(&mut render_pass);
+        for (index, pipeline) in self.pipelines.iter().enumerate() {
+            if let Some(profiler) = profiler.as_deref_mut() {
+                profiler.begin_pipeline(&mut render_pass, self.index, index);
+            }
+            pipeline.render(&mut render_pass, width, height, tile, phase);
+            if let Some(profiler) = profiler.as_deref_mut() {
+                profiler.end_pipeline(&mut render_pass);
+            }
+        }
+        drop(render_pass);
+
+        if let Some(profiler) = profiler {
+            profiler.end_pass(encoder);
         }
     }
 }
 
 struct Depth {
     format: wgpu::TextureFormat,
+    ops: wgpu::Operations<f32>,
+    compare: wgpu::CompareFunction,
 }
 
 impl Depth {
-    fn stencil(&self) -> wgpu::DepthStencilState {
+    fn stencil(&self, depth_test: bool) -> wgpu::DepthStencilState {
         wgpu::DepthStencilState {
             format: self.format,
-            depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::LessEqual,
+            depth_write_enabled: depth_test,
+            depth_compare: if depth_test { self.compare } else { wgpu::CompareFunction::Always },
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }
     }
 
-    fn begin_render_pass(&self, wg: &WebGPUDevice, width: u32, height: u32) -> RuntimeDepth {
-        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
-        let texture = wg.create_texture("Depth", width, height, usage, self.format);
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        RuntimeDepth { _texture: texture, view }
+    fn attachment<'a>(&self, view: &'a wgpu::TextureView) -> wgpu::RenderPassDepthStencilAttachment<'a> {
+        wgpu::RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(self.ops),
+            stencil_ops: None,
+        }
     }
 }
 
-struct RuntimeDepth {
+//
+// DepthBuffers
+
+// Depth textures by format and size, shared by the passes of a frame.
+#[derive(Default)]
+struct DepthBuffers {
+    buffers: Vec<DepthBuffer>,
+}
+
+struct DepthBuffer {
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    used: bool,
     _texture: wgpu::Texture,
-    view: wgpu::TextureView,
+    view: Rc<wgpu::TextureView>,
 }
 
-impl RuntimeDepth {
-    fn attachment(&self) -> wgpu::RenderPassDepthStencilAttachment {
-        wgpu::RenderPassDepthStencilAttachment {
-            view: &self.view,
-            depth_ops: Some(wgpu::Operations {
-                load: wgpu::LoadOp::Clear(1.0),
-                store: false,
-            }),
-            stencil_ops: None,
+impl DepthBuffers {
+    // Drops the buffers no pass used in the previous frame
+    fn begin_frame(&mut self) {
+        self.buffers.retain(|buffer| buffer.used);
+        for buffer in &mut self.buffers {
+            buffer.used = false;
         }
     }
+
+    fn view(&mut self, wg: &WebGPUDevice, format: wgpu::TextureFormat, width: u32, height: u32) -> Rc<wgpu::TextureView> {
+        if let Some(buffer) = self.buffers.iter_mut()
+            .find(|buffer| buffer.format == format && buffer.width == width && buffer.height == height)
+        {
+            buffer.used = true;
+            return buffer.view.clone();
+        }
+
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
+        let texture = wg.create_texture("Depth", width, height, usage, format);
+        let view = Rc::new(texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        self.buffers.push(DepthBuffer { format, width, height, used: true, _texture: texture, view: view.clone() });
+        view
+    }
 }
 
 //
 // Pipeline
 
 struct Pipeline {
+    label: String,
     pipeline: wgpu::RenderPipeline,
     vertices: u32,
     vertex_buffers: Vec<Rc<wgpu::Buffer>>,
     index_buffer: Option<SmartBuffer<wgpu::IndexFormat>>,
     uniform_groups: Vec<wgpu::BindGroup>,
-    textures_groups: Vec<wgpu::BindGroup>,
-    instances: u32
+    textures: Textures,
+    shared: Option<Rc<Uniforms>>,
+    target: Option<Uniform<TargetUniform>>,
+    instances: u32,
+    viewport: Viewport,
 }
 
 impl Pipeline {
+    const UNIFORMS_GROUP: u32 = 0;
+    const TEXTURES_GROUP: u32 = 1;
+    const SHARED_GROUP: u32 = 2;
+
+    #[allow(clippy::too_many_arguments)]
     fn new(
         conf: PipelineConfiguration,
+        index: usize,
         wg: &WebGPUDevice,
-        depth_stencil: Option<wgpu::DepthStencilState>
+        shared: Option<Rc<Uniforms>>,
+        inputs: Vec<Input>,
+        resources: &Resources,
+        format: wgpu::TextureFormat,
+        depth_stencil: Option<wgpu::DepthStencilState>,
+        viewport: Viewport,
     ) -> Result<(Pipeline, Vec<ContentBox>)> {
         let (vertex_buffers, vertex_decls): (Vec<SmartBuffer<wgpu::VertexBufferLayout>>, Vec<String>) =
             conf.vertices.into_iter()
@@ -284,21 +680,31 @@ impl Pipeline {
                 .unzip();
         let index_buffer = conf.indices
             .map(|descriptor| descriptor.create_buffer(wg));
-        let textures = Textures::new(wg, &conf.textures)?;
-
-        let uniforms = Uniforms::new(conf.uniforms, wg);
+        let textures = Textures::new(wg, &conf.textures, inputs, resources, Self::TEXTURES_GROUP)?;
+        // The frame globals are among the render-level uniforms
+        let shared = shared.filter(|_| conf.shared_uniforms || conf.frame_uniform);
+
+        let mut uniforms = conf.uniforms;
+        let target = conf.frame_uniform.then(|| uniforms
+            .add("Target", TargetUniform::default(), wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT)
+            .value());
+        let uniforms = Uniforms::new(uniforms, wg, Self::UNIFORMS_GROUP);
+        let mut layouts = vec![&uniforms.variants.layout, &textures.layout];
+        layouts.extend(shared.as_ref().map(|shared| &shared.variants.layout));
 
         let render_pipeline = Self::create_pipeline(
             &wg.device,
-            wg.texture_format,
+            wgpu::ColorTargetState { format, blend: conf.blend, write_mask: wgpu::ColorWrites::ALL },
             &vertex_buffers.iter()
                 .map(|buffer| buffer.format.clone())
                 .collect::<Vec<_>>(),
-            &[&uniforms.variants.layout, &textures.variants.layout],
+            &layouts,
             format!(
-                "{}\n{}\n{}",
+                "{}\n{}\n{}\n{}\n{}",
                 vertex_decls.join("\n"),
+                shared.as_ref().map_or("", |shared| shared.declarations.as_str()),
                 uniforms.declarations,
+                textures.declarations,
                 conf.shader_source
             ).as_str(),
             wgpu::PrimitiveState {
@@ -311,6 +717,7 @@ impl Pipeline {
         );
 
         let pipeline = Pipeline {
+            label: conf.label.unwrap_or_else(|| format!("Pipeline {index}")),
             pipeline: render_pipeline,
             vertices: usize_as_u32(conf.vertex_count),
             vertex_buffers: vertex_buffers.into_iter()
@@ -318,15 +725,43 @@ impl Pipeline {
                 .collect(),
             index_buffer,
             uniform_groups: uniforms.variants.groups,
-            textures_groups: textures.variants.groups,
-            instances: usize_as_u32(uniforms.instances)
+            textures,
+            shared,
+            target,
+            instances: usize_as_u32(uniforms.instances),
+            viewport,
         };
-        Ok((pipeline, conf.listeners))
+
+        let listeners = if viewport == Viewport::FULL {
+            conf.listeners
+        } else {
+            conf.listeners.into_iter()
+                .map(|listener| Box::new(ViewportContent::new(viewport, listener)) as ContentBox)
+                .collect()
+        };
+        Ok((pipeline, listeners))
+    }
+
+    fn bind(&mut self, wg: &WebGPUDevice, resources: &Resources) {
+        if self.textures.is_transient() {
+            self.textures.bind(wg, resources);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn resize_target(&mut self, width: u32, height: u32) {
+        if let Some(target) = self.target.as_mut() {
+            let rect = self.viewport.pixels(width, height);
+            let size = [rect.width as f32, rect.height as f32];
+            if target.size != size {
+                target.as_mut().size = size;
+            }
+        }
     }
 
     fn create_pipeline<'a>(
         device: &wgpu::Device,
-        format: wgpu::TextureFormat,
+        target: wgpu::ColorTargetState,
         vertex_buffer_layouts: &'a [wgpu::VertexBufferLayout<'a>],
         bind_group_layouts: &[&wgpu::BindGroupLayout],
         shader_source: &str,
@@ -356,7 +791,7 @@ impl Pipeline {
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: "fs_main",
-                targets: &[Some(format.into())],
+                targets: &[Some(target)],
             }),
             primitive,
             depth_stencil,
@@ -365,19 +800,30 @@ impl Pipeline {
         })
     }
 
-    fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+    fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, width: u32, height: u32, tile: &Tile, phase: usize) {
+        let Some((viewport, _)) = tile.clip(&self.viewport) else {
+            return;
+        };
+        if !viewport.apply(render_pass, width, height) {
+            return;
+        }
         render_pass.set_pipeline(&self.pipeline);
 
         for (slot, buffer) in self.vertex_buffers.iter().enumerate() {
             render_pass.set_vertex_buffer(usize_as_u32(slot), buffer.slice(..));
         }
 
-        for group in &self.textures_groups {
-            render_pass.set_bind_group(1, group, &[]);
+        let groups = &self.textures.groups;
+        if let Some(group) = groups.get(phase % groups.len().max(1)) {
+            render_pass.set_bind_group(Self::TEXTURES_GROUP, group, &[]);
+        }
+
+        if let Some(group) = self.shared.as_ref().and_then(|shared| shared.variants.groups.first()) {
+            render_pass.set_bind_group(Self::SHARED_GROUP, group, &[]);
         }
 
         for group in &self.uniform_groups {
-            render_pass.set_bind_group(0, group, &[]);
+            render_pass.set_bind_group(Self::UNIFORMS_GROUP, group, &[]);
 
             match self.index_buffer.as_ref() {
                 None => render_pass.draw(0..self.vertices, 0..self.instances),
@@ -390,127 +836,121 @@ impl Pipeline {
     }
 }
 
-
 //
-// SaveImage, SaveImageData
+// ComputePipeline
 
-struct SaveImage {
-    filename: String,
-    data: RefCell<Option<SaveImageData>>,
+struct ComputePipeline {
+    label: String,
+    pipeline: wgpu::ComputePipeline,
+    uniform_groups: Vec<wgpu::BindGroup>,
+    textures: Textures,
+    shared: Option<Rc<Uniforms>>,
+    storage_group: u32,
+    storage: Vec<wgpu::BindGroup>,
+    workgroups: (u32, u32),
 }
 
-struct SaveImageData {
-    width: u32,
-    height: u32,
-    texture: wgpu::Texture,
-    buffer: Arc<wgpu::Buffer>,
-    padded_bytes_per_row: u32,
-}
+impl ComputePipeline {
+    fn new(
+        conf: ComputeConfiguration,
+        wg: &WebGPUDevice,
+        shared: Option<Rc<Uniforms>>,
+        inputs: Vec<Input>,
+        resources: &Resources,
+        target: Resource,
+    ) -> Result<(ComputePipeline, Vec<ContentBox>)> {
+        let Resource::Feedback(index) = target else {
+            bail!("Compute pipeline target should be a feedback texture");
+        };
+        let feedback = resources.feedbacks.get(index);
+        let textures = Textures::new(wg, &conf.textures, inputs, resources, Pipeline::TEXTURES_GROUP)?;
+        let shared = shared.filter(|_| conf.shared_uniforms || conf.frame_uniform);
+
+        #[allow(clippy::cast_precision_loss)]
+        let size = TargetUniform { size: [feedback.width as f32, feedback.height as f32] };
+        let mut uniforms = conf.uniforms;
+        if conf.frame_uniform {
+            uniforms.add("Target", size, wgpu::ShaderStages::COMPUTE).value();
+        }
+        let uniforms = Uniforms::new(uniforms, wg, Pipeline::UNIFORMS_GROUP);
+
+        // Storage follows the shared uniforms when bound, group indices being contiguous
+        let mut layouts = vec![&uniforms.variants.layout, &textures.layout];
+        layouts.extend(shared.as_ref().map(|shared| &shared.variants.layout));
+        let storage_group = usize_as_u32(layouts.len());
+        let views = (0..Feedbacks::PHASES).map(|phase| feedback.target(phase)).collect();
+        let storage = BindGroupVariants::new(
+            wg,
+            "Storage",
+            vec![storage_binding(views, feedback.format)],
+            (0..Feedbacks::PHASES).map(|phase| vec![phase]).collect(),
+        );
+        layouts.push(&storage.layout);
+
+        let source = format!(
+            "{}\n{}\n{}\n{}\n{}",
+            shared.as_ref().map_or("", |shared| shared.declarations.as_str()),
+            uniforms.declarations,
+            textures.declarations,
+            storage_declaration(&feedback.name, feedback.format, storage_group, 0),
+            conf.shader_source,
+        );
+        let shader = wg.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(&source)),
+        });
+        let pipeline_layout = wg.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &layouts,
+            push_constant_ranges: &[],
+        });
+        let pipeline = wg.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "cs_main",
+        });
 
-impl SaveImage {
-    fn new(filename: String) -> Self {
-        Self { filename, data: RefCell::new(None) }
+        let (group_width, group_height) = conf.workgroup_size;
+        let pipeline = ComputePipeline {
+            label: conf.label.unwrap_or_else(|| "Compute".to_owned()),
+            pipeline,
+            uniform_groups: uniforms.variants.groups,
+            textures,
+            shared,
+            storage_group,
+            storage: storage.groups,
+            workgroups: (feedback.width.div_ceil(group_width.max(1)), feedback.height.div_ceil(group_height.max(1))),
+        };
+        Ok((pipeline, conf.listeners))
     }
 
-    #[allow(clippy::pattern_type_mismatch)]
-    fn render(&self, renderer: &WebGPURender, width: u32, height: u32) {
-        self.update_data(&renderer.wg, width, height);
-        if let Some(data) = &*self.data.borrow() {
-            data.render(renderer, self.filename.clone());
+    fn bind(&mut self, wg: &WebGPUDevice, resources: &Resources) {
+        if self.textures.is_transient() {
+            self.textures.bind(wg, resources);
         }
     }
 
-    #[allow(clippy::pattern_type_mismatch)]
-    fn update_data(&self, wg: &WebGPUDevice, width: u32, height: u32) {
-        if let Some(ref data) = &*self.data.borrow() {
-            if data.width == width && data.height == height {
-                return;
-            }
+    fn dispatch<'a>(&'a self, compute_pass: &mut wgpu::ComputePass<'a>, phase: usize) {
+        compute_pass.set_pipeline(&self.pipeline);
+
+        let groups = &self.textures.groups;
+        if let Some(group) = groups.get(phase % groups.len().max(1)) {
+            compute_pass.set_bind_group(Pipeline::TEXTURES_GROUP, group, &[]);
         }
 
-        *self.data.borrow_mut() = Some(SaveImageData::new(wg, width, height));
-    }
-}
+        if let Some(group) = self.shared.as_ref().and_then(|shared| shared.variants.groups.first()) {
+            compute_pass.set_bind_group(Pipeline::SHARED_GROUP, group, &[]);
+        }
 
-impl SaveImageData {
-    const U32_SIZE: u32 = size_of::<u32>() as u32;
-    const ALIGN: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-
-    fn new(wg: &WebGPUDevice, width: u32, height: u32) -> SaveImageData {
-        let texture = wg.create_texture(
-            "Save as image",
-            width,
-            height,
-            wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
-            wg.texture_format,
-        );
-        let padded_bytes_per_row = ((width * Self::U32_SIZE - 1) / Self::ALIGN + 1) * Self::ALIGN;
-
-        let buffer = Arc::new(wg.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Export"),
-            size: wgpu::BufferAddress::from(padded_bytes_per_row * height),
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        }));
-        SaveImageData {
-            width,
-            height,
-            texture,
-            buffer,
-            padded_bytes_per_row,
-        }
-    }
-
-    fn render(&self, renderer: &WebGPURender, filename: String) {
-        let mut encoder = renderer.render_to_texture(&self.texture);
-
-        encoder.copy_texture_to_buffer(
-            self.texture.as_image_copy(),
-            wgpu::ImageCopyBuffer {
-                buffer: &self.buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(self.padded_bytes_per_row),
-                    rows_per_image: Some(self.height),
-                },
-            },
-            self.texture.size(),
-        );
+        if let Some(group) = self.storage.get(phase % Feedbacks::PHASES) {
+            compute_pass.set_bind_group(self.storage_group, group, &[]);
+        }
 
-        let submission_index = renderer.wg.queue.submit(Some(encoder.finish()));
-
-        let width = self.width;
-        let height = self.height;
-        let padded_bytes_per_row = self.padded_bytes_per_row;
-        let buffer = self.buffer.clone();
-        buffer.clone().slice(..).map_async(wgpu::MapMode::Read, move |_| {
-            let mapped = buffer.slice(..).get_mapped_range();
-            Self::save_image(filename, &mapped, width, height, padded_bytes_per_row);
-            drop(mapped);
-            buffer.unmap();
-        });
-        renderer.wg.device.poll(wgpu::Maintain::WaitForSubmissionIndex(submission_index));
-    }
-
-    #[allow(clippy::indexing_slicing, clippy::identity_op)]
-    fn save_image(filename: String, data: &[u8], width: u32, height: u32, padded_bytes_per_row: u32) {
-        use image::{ImageBuffer, Rgba};
-        let mut pixels = vec![0; (width * height * Self::U32_SIZE) as usize];
-        for r in 0..height {
-            let row_pad = r * padded_bytes_per_row;
-            let row_unpad = r * width * Self::U32_SIZE;
-            for c in 0..width {
-                let pad = (row_pad + c * Self::U32_SIZE) as usize;
-                let unpad = (row_unpad + c * Self::U32_SIZE) as usize;
-                pixels[unpad + 0] = data[pad + 2];
-                pixels[unpad + 1] = data[pad + 1];
-                pixels[unpad + 2] = data[pad + 0];
-                pixels[unpad + 3] = data[pad + 3];
-            }
+        let (x, y) = self.workgroups;
+        for group in &self.uniform_groups {
+            compute_pass.set_bind_group(Pipeline::UNIFORMS_GROUP, group, &[]);
+            compute_pass.dispatch_workgroups(x, y, 1);
         }
-        ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, pixels)
-            .expect("image created")
-            .save(filename)
-            .expect("image saved");
     }
 }
@@ -1,22 +1,23 @@
 use core::ops::DerefMut;
 
+use image::io::Reader as ImageReader;
 use winit::{
-    event::{Event, VirtualKeyCode::Escape, WindowEvent},
+    event::{DeviceEvent, Event, VirtualKeyCode::Escape, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
-    window::Window,
+    monitor::{MonitorHandle, VideoMode},
+    window::{Fullscreen, Icon, WindowBuilder, WindowLevel},
 };
 
-use crate::{Content, WindowConfiguration};
+use crate::{Content, FrameClock, InputEvent, InputState, WindowConfiguration, WindowFullscreen};
 use crate::window_api::RawWindow;
 
-pub fn show<F>(config: &WindowConfiguration, factory: F) -> ! where
+pub fn show<F>(config: &WindowConfiguration, mut clock: FrameClock, factory: F) -> ! where
     F: FnOnce(&dyn RawWindow) -> Box<dyn Content<()>>,
 {
-    #![allow(clippy::print_stdout, clippy::use_debug)]
-
     let event_loop = EventLoop::new();
-    let window = Window::new(&event_loop).expect("Create window");
-    window.set_title(config.title);
+    let window = builder(config, &event_loop)
+        .build(&event_loop)
+        .expect("Create window");
 
     let mut contents = factory(&window);
 
@@ -24,29 +25,117 @@ pub fn show<F>(config: &WindowConfiguration, factory: F) -> ! where
         .deref_mut()
         .resize((), window.inner_size().width, window.inner_size().height);
 
-    let render_start_time = std::time::Instant::now();
+    let mut input_state = InputState::default();
     event_loop.run(move |event, _, control_flow| {
         *control_flow = ControlFlow::Wait;
-        match event {
+        let input = match event {
             Event::WindowEvent { event: window_event, .. } => match window_event {
-                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                WindowEvent::Resized(size) =>
-                    contents.resize((), size.width, size.height),
-                WindowEvent::ScaleFactorChanged { new_inner_size, .. } =>
-                    contents.resize((), new_inner_size.width, new_inner_size.height),
-                WindowEvent::KeyboardInput { input, .. } => {
-                    if input.virtual_keycode == Some(Escape) {
-                        *control_flow = ControlFlow::Exit;
-                    } else {
-                        println!("Key: {:?}", input.virtual_keycode);
-                    }
+                WindowEvent::CloseRequested => {
+                    *control_flow = ControlFlow::Exit;
+                    None
+                }
+                WindowEvent::Resized(size) => {
+                    contents.resize((), size.width, size.height);
+                    None
+                }
+                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    contents.resize((), new_inner_size.width, new_inner_size.height);
+                    None
+                }
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    input_state.set_modifiers(modifiers);
+                    None
                 }
-                _ => (),
+                WindowEvent::KeyboardInput { input, .. } => input.virtual_keycode
+                    .map(|key| InputEvent::Key { key, state: input.state, modifiers: input_state.modifiers() }),
+                WindowEvent::MouseInput { button, state, .. } =>
+                    Some(InputEvent::MouseButton { button, state, modifiers: input_state.modifiers() }),
+                WindowEvent::CursorMoved { position, .. } => {
+                    let size = window.inner_size();
+                    Some(InputEvent::cursor(position, size.width, size.height))
+                }
+                WindowEvent::MouseWheel { delta, .. } => Some(InputEvent::scroll(delta)),
+                #[allow(clippy::cast_possible_truncation)]
+                WindowEvent::TouchpadMagnify { delta, .. } => Some(InputEvent::Magnify { delta: delta as f32 }),
+                WindowEvent::Focused(focused) => Some(InputEvent::Focused(focused)),
+                _ => None,
             },
-            Event::DeviceEvent { event: input, .. } => contents.input((), &input),
-            Event::RedrawRequested(_) => contents.update((), render_start_time.elapsed()),
-            Event::MainEventsCleared => window.request_redraw(),
-            _ => (),
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } =>
+                Some(InputEvent::MouseMotion { delta }),
+            Event::RedrawRequested(_) => {
+                for time in clock.tick() {
+                    contents.update((), &time, &input_state);
+                }
+                contents.redraw(());
+                None
+            }
+            Event::MainEventsCleared => {
+                window.request_redraw();
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(event) = input {
+            if event.is_pressed(Escape) {
+                *control_flow = ControlFlow::Exit;
+            }
+            if clock.has_hotkeys() {
+                clock.input(&event);
+            }
+            input_state.apply(&event);
+            contents.input((), &event);
         }
     });
 }
+
+fn builder(config: &WindowConfiguration, event_loop: &EventLoop<()>) -> WindowBuilder {
+    let mut builder = WindowBuilder::new()
+        .with_title(config.title)
+        .with_resizable(config.resizable)
+        .with_decorations(config.decorations)
+        .with_transparent(config.transparent)
+        .with_window_level(if config.always_on_top { WindowLevel::AlwaysOnTop } else { WindowLevel::Normal })
+        .with_window_icon(config.icon.as_deref().map(icon));
+    if let Some(size) = config.size {
+        builder = builder.with_inner_size(size);
+    }
+    if let Some(size) = config.min_size {
+        builder = builder.with_min_inner_size(size);
+    }
+    if let Some(size) = config.max_size {
+        builder = builder.with_max_inner_size(size);
+    }
+    if let Some(fullscreen) = config.fullscreen {
+        let monitor = event_loop.primary_monitor();
+        builder = builder.with_fullscreen(match fullscreen {
+            WindowFullscreen::Borderless => Some(Fullscreen::Borderless(monitor)),
+            WindowFullscreen::Exclusive => monitor
+                .and_then(|monitor| video_mode(&monitor, config))
+                .map(Fullscreen::Exclusive),
+        });
+    }
+    builder
+}
+
+fn video_mode(monitor: &MonitorHandle, config: &WindowConfiguration) -> Option<VideoMode> {
+    let size = config.size
+        .map_or_else(|| monitor.size(), |size| size.to_physical(monitor.scale_factor()));
+    monitor.video_modes()
+        .min_by_key(|mode| (
+            mode.size().width.abs_diff(size.width) + mode.size().height.abs_diff(size.height),
+            u32::MAX - mode.refresh_rate_millihertz(),
+            u16::MAX - mode.bit_depth(),
+        ))
+}
+
+fn icon(file: &str) -> Icon {
+    let image = ImageReader::open(file)
+        .and_then(|reader| reader.with_guessed_format())
+        .expect("Window icon file")
+        .decode()
+        .expect("Window icon image")
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height).expect("Window icon")
+}
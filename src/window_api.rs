@@ -1,6 +1,8 @@
-use core::time::Duration;
+use winit::dpi::{LogicalSize, PhysicalSize, Size};
 
-use winit::event::DeviceEvent;
+use crate::clock::FrameTime;
+use crate::input::{InputEvent, InputState};
+use crate::poster::Tile;
 
 
 //
@@ -8,8 +10,10 @@ use winit::event::DeviceEvent;
 
 pub trait Content<T> {
     fn resize(&mut self, _context: T, _width: u32, _height: u32) {}
-    fn update(&mut self, _context: T, _dt: Duration) {}
-    fn input(&mut self, _context: T, _event: &DeviceEvent) {}
+    fn update(&mut self, _context: T, _time: &FrameTime, _input: &InputState) {}
+    fn redraw(&mut self, _context: T) {}
+    fn input(&mut self, _context: T, _event: &InputEvent) {}
+    fn tile(&mut self, _context: T, _tile: &Tile) {}
 }
 
 //
@@ -40,23 +44,121 @@ impl<T: Clone> Content<T> for CompositeContent<T> {
         }
     }
 
-    fn update(&mut self, context: T, dt: Duration) {
+    fn update(&mut self, context: T, time: &FrameTime, input: &InputState) {
         for part in &mut self.parts {
-            part.update(context.clone(), dt);
+            part.update(context.clone(), time, input);
         }
     }
 
-    fn input(&mut self, context: T, event: &DeviceEvent) {
+    fn redraw(&mut self, context: T) {
+        for part in &mut self.parts {
+            part.redraw(context.clone());
+        }
+    }
+
+    fn input(&mut self, context: T, event: &InputEvent) {
         for part in &mut self.parts {
             part.input(context.clone(), event);
         }
     }
+
+    fn tile(&mut self, context: T, tile: &Tile) {
+        for part in &mut self.parts {
+            part.tile(context.clone(), tile);
+        }
+    }
 }
 
 // WindowConfiguration
 
+#[must_use]
 pub struct WindowConfiguration<'a> {
     pub title: &'a str,
+    pub size: Option<Size>,
+    pub min_size: Option<Size>,
+    pub max_size: Option<Size>,
+    pub fullscreen: Option<WindowFullscreen>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub transparent: bool,
+    pub always_on_top: bool,
+    pub icon: Option<String>,
+}
+
+impl<'a> WindowConfiguration<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            size: None,
+            min_size: None,
+            max_size: None,
+            fullscreen: None,
+            resizable: true,
+            decorations: true,
+            transparent: false,
+            always_on_top: false,
+            icon: None,
+        }
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some(PhysicalSize::new(width, height).into());
+        self
+    }
+
+    pub fn with_logical_size(mut self, width: f64, height: f64) -> Self {
+        self.size = Some(LogicalSize::new(width, height).into());
+        self
+    }
+
+    pub fn with_min_size<S: Into<Size>>(mut self, size: S) -> Self {
+        self.min_size = Some(size.into());
+        self
+    }
+
+    pub fn with_max_size<S: Into<Size>>(mut self, size: S) -> Self {
+        self.max_size = Some(size.into());
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: Option<WindowFullscreen>) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    pub fn with_icon(mut self, file: &str) -> Self {
+        self.icon = Some(file.to_owned());
+        self
+    }
+}
+
+// WindowFullscreen
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WindowFullscreen {
+    Borderless,
+    Exclusive,
 }
 
 // RawWindow